@@ -1,123 +1,284 @@
+use std::cell::RefCell;
+use std::cmp::Ordering;
+use std::collections::hash_map::DefaultHasher;
 use std::collections::{HashMap, VecDeque};
+use std::convert::TryFrom;
+use std::fs::{self, OpenOptions};
+use std::hash::{Hash, Hasher};
 
 use crate::lcore::*;
+use crate::regex::Regex;
 use std::io::{self, Write};
 use std::iter::FromIterator;
-use std::process::exit;
+use std::path::Path;
+use std::process::{exit, Command};
+use std::sync::atomic::{self, AtomicUsize};
+use std::time::Instant;
+
+static GENSYM_COUNTER: AtomicUsize = AtomicUsize::new(0);
+static MEMO_COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+type NativeFunc =
+    fn(&mut Value, &mut Environment) -> Result<Value, LCoreError>;
+
+thread_local! {
+    /// The name each builtin was registered under by `import_builtins`, keyed
+    /// by the function's address.
+    static FUNC_NAMES: RefCell<HashMap<usize, String>> =
+        RefCell::new(HashMap::new());
+}
+
+/// The name a builtin was registered under. Builtins bound to several names
+/// use the first of them alphabetically.
+pub fn lcore_func_name(f: &NativeFunc) -> Option<String> {
+    FUNC_NAMES.with(|names| names.borrow().get(&(*f as usize)).cloned())
+}
+
+fn lcore_register_func_names(symbol_table: &Environment) {
+    FUNC_NAMES.with(|names| {
+        let mut names = names.borrow_mut();
+        for (name, value) in symbol_table.bindings() {
+            if let Value::Func { f } = value {
+                let entry = names.entry(f as usize).or_insert(name.clone());
+                if name < *entry {
+                    *entry = name;
+                }
+            }
+        }
+    });
+}
 
-pub fn lcore_print_value(args: &mut Value) -> Result<Value, LCoreError> {
-    fn print_string(v: &String, repr: bool) {
+/// Write the display form of a value to `out`, or the re-readable form (with
+/// quoted Strings) if `repr` is set.
+pub fn lcore_write_value(
+    out: &mut dyn Write,
+    value: &Value,
+    repr: bool,
+) -> io::Result<()> {
+    fn print_string(
+        out: &mut dyn Write,
+        v: &String,
+        repr: bool,
+    ) -> io::Result<()> {
         if repr {
             // print!("{}", v);
             // print!("\"{}\"", v);
-            print!("\"");
-            io::stdout().write(v.as_bytes()).ok();
-            print!("\"");
+            write!(out, "\"")?;
+            out.write_all(v.as_bytes())?;
+            write!(out, "\"")
         } else {
             // print!("{}", &v[1 .. v.len() - 1]);
             // print!("{}", v);
-            io::stdout().write(v.as_bytes()).ok();
+            out.write_all(v.as_bytes())
+        }
+    }
+
+    fn print_char(
+        out: &mut dyn Write,
+        v: &char,
+        repr: bool,
+    ) -> io::Result<()> {
+        if repr {
+            match v {
+                ' ' => write!(out, "#\\space"),
+                '\n' => write!(out, "#\\newline"),
+                '\t' => write!(out, "#\\tab"),
+                _ => write!(out, "#\\{}", v),
+            }
+        } else {
+            write!(out, "{}", v)
         }
     }
 
-    fn print_boolean(v: &bool, repr: bool) {
-        print!("{}", if *v { "True" } else { "False" });
+    fn print_boolean(
+        out: &mut dyn Write,
+        v: &bool,
+        repr: bool,
+    ) -> io::Result<()> {
+        write!(out, "{}", if *v { "True" } else { "False" })
     }
 
-    fn print_int(v: &i64, repr: bool) {
-        print!("{}", v);
+    fn print_int(out: &mut dyn Write, v: &i64, repr: bool) -> io::Result<()> {
+        write!(out, "{}", v)
     }
 
-    fn print_float(v: &f64, repr: bool) {
-        print!("{}", v);
+    fn print_float(
+        out: &mut dyn Write,
+        v: &f64,
+        repr: bool,
+    ) -> io::Result<()> {
+        // Display never uses exponents, but drops the decimal point from
+        // whole numbers, which would then read back as Ints
+        let text = v.to_string();
+        if text.contains(['.', 'e'])
+            || text.contains("inf")
+            || text.contains("NaN")
+        {
+            write!(out, "{}", text)
+        } else {
+            write!(out, "{}.0", text)
+        }
     }
 
-    fn print_null() {
-        print!("Null");
+    fn print_null(out: &mut dyn Write) -> io::Result<()> {
+        write!(out, "Null")
     }
 
-    fn print_array(v: &Vec<Value>, repr: bool) {
-        let length = v.len();
-        let mut count = 0;
-        print!("[");
-        for value in v {
-            print_value(value, true);
+    fn print_array(
+        out: &mut dyn Write,
+        v: &[Value],
+        repr: bool,
+    ) -> io::Result<()> {
+        write!(out, "[")?;
+        for (i, value) in v.iter().enumerate() {
+            // Quoted calls print like source: `(f x)`, not `( f x )`
+            let after_open = i > 0 && matches!(v[i - 1], Value::OpenFunc);
+            if i > 0 && !after_open && !matches!(value, Value::CloseFunc) {
+                write!(out, " ")?;
+            }
 
-            count += 1;
-            if count < length {
-                // print!(", ");
-                print!(" ");
+            print_value(out, value, true)?;
+        }
+        write!(out, "]")
+    }
+
+    fn print_bytes(out: &mut dyn Write, v: &[u8]) -> io::Result<()> {
+        write!(out, "#bytes[")?;
+        for (i, byte) in v.iter().enumerate() {
+            if i > 0 {
+                write!(out, " ")?;
             }
+            write!(out, "{}", byte)?;
         }
-        print!("]");
+        write!(out, "]")
     }
 
     fn print_func(
-        v: &fn(&mut Value, &mut Environment) -> Result<Value, LCoreError>,
+        out: &mut dyn Write,
+        v: &NativeFunc,
         repr: bool,
-    ) {
-        print!("<Func at {:p}>", v);
+    ) -> io::Result<()> {
+        match lcore_func_name(v) {
+            Some(name) => write!(out, "<Func {}>", name),
+            None => write!(out, "<Func at {:p}>", v),
+        }
     }
 
-    fn print_quote(v: &Box<Value>, repr: bool) {
-        // TODO(pebaz): Choose which one is better:
-
-        // 1.
-        print!("(quote ");
-        print_value(v, repr);
-        print!(")");
-
-        // 2.
-        // print!("'");
-        // print_value(v, repr);
+    fn print_quote(
+        out: &mut dyn Write,
+        v: &Box<Value>,
+        repr: bool,
+    ) -> io::Result<()> {
+        // Print the way quotes are written so the result can be read back
+        write!(out, "'")?;
+        print_value(out, v, repr)
     }
 
-    fn print_dict(v: &HashMap<Value, Value>, repr: bool) {
-        print!("{{ ");
+    fn print_dict(
+        out: &mut dyn Write,
+        v: &HashMap<Value, Value>,
+        repr: bool,
+    ) -> io::Result<()> {
+        write!(out, "{{ ")?;
         // print!("{:?}", v);
         let length = v.len();
         let mut count = 0;
 
         for (key, value) in v {
-            print_value(key, true);
-            print!(": ");
-            print_value(value, true);
+            print_value(out, key, true)?;
+            write!(out, ": ")?;
+            print_value(out, value, true)?;
 
             count += 1;
             if count < length {
-                print!(", ");
+                write!(out, ", ")?;
                 // print!(" ");
             }
         }
 
-        print!(" }}");
+        write!(out, " }}")
+    }
+
+    fn print_struct(
+        out: &mut dyn Write,
+        name: &String,
+        fields: &Vec<(String, Value)>,
+        repr: bool,
+    ) -> io::Result<()> {
+        write!(out, "{} {{ ", name)?;
+        let length = fields.len();
+        let mut count = 0;
+
+        for (field, value) in fields {
+            write!(out, "{}: ", field)?;
+            print_value(out, value, true)?;
+
+            count += 1;
+            if count < length {
+                write!(out, ", ")?;
+            }
+        }
+
+        write!(out, " }}")
     }
 
-    fn print_value(value: &Value, repr: bool) {
+    fn print_value(
+        out: &mut dyn Write,
+        value: &Value,
+        repr: bool,
+    ) -> io::Result<()> {
         match value {
             // Print, stripping out first and last double quotes `"`
-            Value::String(v) => print_string(v, repr),
-            Value::Boolean(v) => print_boolean(v, repr),
-            Value::Int(v) => print_int(v, repr),
-            Value::Float(v) => print_float(v, repr),
-            Value::Array(v) => print_array(v, repr),
-            Value::Func { f: v } => print_func(v, repr),
-            Value::Null => print_null(),
+            Value::String(v) => print_string(out, v, repr),
+            Value::Char(v) => print_char(out, v, repr),
+            Value::Boolean(v) => print_boolean(out, v, repr),
+            Value::Int(v) => print_int(out, v, repr),
+            Value::Float(v) => print_float(out, v, repr),
+            Value::Array(v) => print_array(out, v, repr),
+            Value::Bytes(v) => print_bytes(out, v),
+            Value::Func { f: v } => print_func(out, v, repr),
+            Value::Null => print_null(out),
             Value::Identifier(v) => {
                 // TODO
                 // Will only get here if value was quoted
                 // CHECK ON THIS LATER, not sure any more
                 // print!("'{}", v);
-                print!("{}", v);
+                write!(out, "{}", v)
+            }
+            Value::Quote(v) => print_quote(out, v, true),
+            Value::Dict(v) => print_dict(out, v, repr),
+            Value::Composed(v) => write!(out, "<Composed Func>"),
+            Value::Partial { .. } => write!(out, "<Partial Func>"),
+            Value::Struct { name, fields } => {
+                print_struct(out, name, fields, repr)
             }
-            Value::Quote(v) => print_quote(v, true),
-            Value::Dict(v) => print_dict(v, repr),
-            Value::OpenFunc => print!("("),
-            Value::CloseFunc => print!(")"),
-            _ => {}
+            Value::StructType { name, .. } => write!(out, "<Struct {}>", name),
+            Value::Traced { func, .. }
+            | Value::Memoized { func, .. }
+            | Value::Namespaced { func, .. } => print_value(out, func, repr),
+            Value::OpenFunc => write!(out, "("),
+            Value::CloseFunc => write!(out, ")"),
+            Value::BackTick => write!(out, "`"),
+            Value::Comma => write!(out, ","),
+            _ => Ok(()),
         }
     }
 
+    print_value(out, value, repr)
+}
+
+/// Render a value the same way `lcore_write_value` would print it.
+pub fn lcore_value_to_string(value: &Value, repr: bool) -> String {
+    let mut buffer = Vec::new();
+    lcore_write_value(&mut buffer, value, repr).ok();
+    String::from_utf8_lossy(&buffer).into_owned()
+}
+
+/// Print the single value in `args` to `out`, in display form.
+pub fn lcore_print_value(
+    out: &mut dyn Write,
+    args: &Value,
+) -> Result<Value, LCoreError> {
     let args = args.as_array();
 
     if args.len() > 1 {
@@ -129,7 +290,7 @@ pub fn lcore_print_value(args: &mut Value) -> Result<Value, LCoreError> {
 
     let value = args.iter().next().unwrap();
 
-    print_value(value, false);
+    lcore_write_value(out, value, false).ok();
 
     Ok(Value::Null)
 }
@@ -138,7 +299,7 @@ pub fn lcore_prin(
     args: &mut Value,
     symbol_table: &mut Environment,
 ) -> Result<Value, LCoreError> {
-    lcore_print_value(args).ok();
+    lcore_print_value(symbol_table.output(), args).ok();
     Ok(Value::Null)
 }
 
@@ -146,8 +307,46 @@ pub fn lcore_print(
     args: &mut Value,
     symbol_table: &mut Environment,
 ) -> Result<Value, LCoreError> {
-    lcore_print_value(args).ok();
-    println!("");
+    lcore_print_value(symbol_table.output(), args).ok();
+    writeln!(symbol_table.output()).ok();
+    Ok(Value::Null)
+}
+
+/// Run a quoted body and return everything it printed as a String instead
+/// of writing it out. Captures nest, so only the innermost one sees output.
+pub fn lcore_with_output_to_string(
+    args: &mut Value,
+    symbol_table: &mut Environment,
+) -> Result<Value, LCoreError> {
+    let [body] = lcore_take_args(args, "with-output-to-string")?;
+    let body = body.clone();
+
+    symbol_table.begin_capture();
+    let result = lcore_run_body(&body, "with-output-to-string", symbol_table);
+    let captured = symbol_table.end_capture();
+
+    result?;
+    Ok(Value::String(
+        String::from_utf8_lossy(&captured).into_owned(),
+    ))
+}
+
+/// Like `prin`, but writes to stderr so diagnostics stay out of the output.
+pub fn lcore_eprint(
+    args: &mut Value,
+    symbol_table: &mut Environment,
+) -> Result<Value, LCoreError> {
+    lcore_print_value(&mut io::stderr(), args).ok();
+    Ok(Value::Null)
+}
+
+/// Like `print`, but writes to stderr.
+pub fn lcore_eprintln(
+    args: &mut Value,
+    symbol_table: &mut Environment,
+) -> Result<Value, LCoreError> {
+    lcore_print_value(&mut io::stderr(), args).ok();
+    eprintln!();
     Ok(Value::Null)
 }
 
@@ -158,54 +357,153 @@ pub fn lcore_quit(
     exit(0);
 }
 
+/// Take the `N` arguments of a builtin call, failing with a uniform
+/// ArgumentError when there are more or fewer.
+fn lcore_take_args<'a, const N: usize>(
+    args: &'a Value,
+    fn_name: &str,
+) -> Result<[&'a Value; N], LCoreError> {
+    let args = args.as_array();
+    if args.len() != N {
+        return Err(lcore_arity_error(fn_name, N, args.len()));
+    }
+
+    Ok(std::array::from_fn(|i| &args[i]))
+}
+
+/// The uniform ArgumentError for a builtin called with the wrong number of
+/// arguments.
+fn lcore_arity_error(
+    fn_name: &str,
+    expected: usize,
+    got: usize,
+) -> LCoreError {
+    LCoreError::ArgumentError(format!(
+        "ArgumentError: \"{}\" expected {} arguments, got {}",
+        fn_name, expected, got
+    ))
+}
+
 pub fn lcore_set(
     args: &mut Value,
     symbol_table: &mut Environment,
 ) -> Result<Value, LCoreError> {
-    let mut args = args.as_array().iter();
+    let [var, value] = lcore_take_args(args, "set")?;
 
-    let var = args
-        .next()
-        .expect("Not enough arguments on call to \"set\": 0/2");
-
-    let value = args
-        .next()
-        .expect("Not enough arguments on call to \"set\": 1/2");
-
-    match var {
+    let bindings = match var {
         // Identifier
-        Value::Identifier(v) => {
-            symbol_table.insert(v.clone().to_string(), value.clone());
+        Value::Identifier(v) => vec![(v.to_string(), value.clone())],
+
+        // Quoted Array of names to destructure into
+        Value::Quote(v) if matches!(**v, Value::Array(..)) => {
+            lcore_destructure(v.as_array(), value)?
         }
 
         // Quoted Identifier
         Value::Quote(v) => {
-            let mystr = v.as_identifier();
-            symbol_table.insert(mystr.clone().to_string(), value.clone());
+            vec![(v.as_identifier().to_string(), value.clone())]
         }
 
-        _ => (),
+        // Array of quoted names to destructure into
+        Value::Array(names) => lcore_destructure(names, value)?,
+
+        _ => vec![],
+    };
+
+    // Check every name first so a destructuring set binds all or nothing
+    for (name, _) in &bindings {
+        lcore_check_not_frozen(name, symbol_table)?;
+    }
+
+    for (name, value) in bindings {
+        symbol_table.insert(name, value);
     }
 
     Ok(Value::Null)
 }
 
+/// Bind a quoted name in the global scope, even from inside a function.
+pub fn lcore_def_global(
+    args: &mut Value,
+    symbol_table: &mut Environment,
+) -> Result<Value, LCoreError> {
+    let name = lcore_quoted_name(args, "def-global")?;
+    let [_, value] = lcore_take_args(args, "def-global")?;
+
+    symbol_table.global_insert(name, value.clone());
+    Ok(Value::Null)
+}
+
+/// Pair up names with the elements of an Array value. A `&` before the last
+/// name binds it to an Array of all the remaining elements, e.g.
+/// `(set '[first & rest] [1 2 3])`.
+fn lcore_destructure(
+    names: &[Value],
+    value: &Value,
+) -> Result<Vec<(String, Value)>, LCoreError> {
+    let names = names
+        .iter()
+        .map(|name| match name {
+            Value::Identifier(n) => Ok(n.clone()),
+            Value::Quote(q) if matches!(**q, Value::Identifier(..)) => {
+                Ok(q.as_identifier().to_string())
+            }
+            other => Err(LCoreError::ArgumentError(format!(
+                "ArgumentError: Cannot bind to {:?}",
+                other
+            ))),
+        })
+        .collect::<Result<Vec<String>, LCoreError>>()?;
+
+    let values = match value {
+        Value::Array(values) => values,
+        other => {
+            return Err(LCoreError::ArgumentError(format!(
+                "ArgumentError: Cannot destructure {:?}",
+                other
+            )))
+        }
+    };
+
+    let (fixed, rest) = match names.iter().position(|n| n == "&") {
+        Some(i) if i + 2 == names.len() => (&names[..i], Some(&names[i + 1])),
+        Some(_) => {
+            return Err(LCoreError::ArgumentError(format!(
+                "ArgumentError: \"&\" must be followed by exactly one name"
+            )))
+        }
+        None => (&names[..], None),
+    };
+
+    if values.len() < fixed.len()
+        || (rest.is_none() && values.len() > fixed.len())
+    {
+        return Err(LCoreError::ArgumentError(format!(
+            "ArgumentError: Cannot destructure {} values into {} names",
+            values.len(),
+            fixed.len()
+        )));
+    }
+
+    let mut bound: Vec<(String, Value)> =
+        fixed.iter().cloned().zip(values.iter().cloned()).collect();
+
+    if let Some(rest) = rest {
+        bound.push((
+            rest.clone(),
+            Value::Array(values[fixed.len()..].to_vec()),
+        ));
+    }
+
+    Ok(bound)
+}
+
 pub fn lcore_loop(
     args: &mut Value,
     symbol_table: &mut Environment,
 ) -> Result<Value, LCoreError> {
+    let [quote, iters, body] = lcore_take_args(args, "loop")?;
     symbol_table.push();
-    let mut args = args.as_array().iter();
-
-    let quote = args
-        .next()
-        .expect("Not enough arguments on call to \"loop\": 0/3");
-    let iters = args
-        .next()
-        .expect("Not enough arguments on call to \"loop\": 1/3");
-    let body = args
-        .next()
-        .expect("Not enough arguments on call to \"loop\": 2/3");
 
     for i in 0..*iters.as_int() {
         let mut loop_body = match body.as_value().clone() {
@@ -217,17 +515,13 @@ pub fn lcore_loop(
             symbol_table.insert(s.clone().to_string(), Value::Int(i));
         }
 
-        if let Err(err) = lcore_interpret(&mut loop_body, symbol_table) {
-            match err {
-                LCoreError::LambdaCoreError(..) => return Err(err),
-                LCoreError::IndexError(..) => return Err(err),
-                LCoreError::ArgumentError(..) => return Err(err),
-                LCoreError::NameError(..) => return Err(err),
-                // LCoreError::ReturnError(v) => println!("CANNOT RETURN FROM
-                // FOR LOOP"),
-                LCoreError::ReturnError => return Err(err),
-                LCoreError::BreakError => break,
+        match lcore_interpret(&mut loop_body, symbol_table) {
+            Err(LCoreError::BreakError) => break,
+            Err(err) => {
+                symbol_table.pop();
+                return Err(err);
             }
+            Ok(..) => (),
         }
     }
 
@@ -237,37 +531,64 @@ pub fn lcore_loop(
 
 /// Stuff the code to run in a list value in the symbol table. Make sure to
 /// store the variables to bind at call time.
+///
+/// Functions are stored as `[[args] [body] doc]`.
 pub fn lcore_defn(
     args: &mut Value,
     symbol_table: &mut Environment,
 ) -> Result<Value, LCoreError> {
     // Identifier
     // Array<Quoted(Identifier)>
-    // Quoted(Array<Value>) (The code to run later)
+    // Quoted(Array<Value>) (The code to run later, maybe with a docstring)
 
-    let mut args = args.as_array().iter();
+    let [name, arguments, body] = lcore_take_args(args, "defn")?;
+
+    let mut code = match body {
+        Value::Quote(q) => match &**q {
+            Value::Array(code) => code.clone(),
+            _ => {
+                return Err(LCoreError::ArgumentError(format!(
+                    "ArgumentError: \"defn\" body must be a quoted Array"
+                )))
+            }
+        },
+        _ => {
+            return Err(LCoreError::ArgumentError(format!(
+                "ArgumentError: \"defn\" body must be a quoted Array"
+            )))
+        }
+    };
+
+    let is_params = match arguments {
+        Value::Array(names) => names.iter().all(Value::is_quoted_identifier),
+        _ => false,
+    };
+    if !is_params {
+        return Err(LCoreError::ArgumentError(format!(
+            "ArgumentError: \"defn\" parameters must be an Array of quoted names"
+        )));
+    }
 
-    let name = args
-        .next()
-        .expect("Not enough arguments on call to \"defn\": 0/3");
-    let arguments = args
-        .next()
-        .expect("Not enough arguments on call to \"defn\": 1/3");
-    let body = args
-        .next()
-        .expect("Not enough arguments on call to \"defn\": 2/3");
+    // NOTE: A leading String in a body with more code after it is a
+    // docstring. It is stored with the function instead of being run.
+    let doc = match code.first() {
+        Some(Value::String(..)) if code.len() > 1 => code.remove(0),
+        _ => Value::Null,
+    };
 
-    let def = Value::Array(vec![arguments.clone(), body.as_value().clone()]);
+    let def = Value::Array(vec![arguments.clone(), Value::Array(code), doc]);
 
     match name {
         // Identifier
         Value::Identifier(v) => {
+            lcore_check_not_frozen(v, symbol_table)?;
             symbol_table.insert(v.clone().to_string(), def);
         }
 
         // Quoted Identifier
         Value::Quote(v) => {
             let mystr = v.as_identifier();
+            lcore_check_not_frozen(mystr, symbol_table)?;
             symbol_table.insert(mystr.clone().to_string(), def);
         }
 
@@ -277,18 +598,133 @@ pub fn lcore_defn(
     Ok(Value::Null)
 }
 
-pub fn lcore_get(
+/// Get the docstring of a LambdaCore function, or Null if it has none.
+pub fn lcore_doc(
     args: &mut Value,
     symbol_table: &mut Environment,
 ) -> Result<Value, LCoreError> {
-    let mut args = args.as_array().iter();
+    let [func] = lcore_take_args(args, "doc")?;
+
+    match func {
+        Value::Array(def) if func.is_callable() => {
+            Ok(def.get(2).cloned().unwrap_or(Value::Null))
+        }
+        func if func.is_callable() => Ok(Value::Null),
+        other => Err(LCoreError::ArgumentError(format!(
+            "ArgumentError: {:?} is not a function",
+            other
+        ))),
+    }
+}
+
+/// A fresh table holding only the builtins.
+fn lcore_builtins() -> HashMap<String, Value> {
+    let mut builtins = Environment::new();
+    builtins.push();
+    import_builtins(&mut builtins);
+    builtins.pop()
+}
+
+fn lcore_describe_value(name: &str, value: &Value) -> String {
+    match value {
+        Value::Func { f } => match lcore_func_name(f) {
+            Some(builtin) if builtin != name => {
+                format!("{}: builtin {}", name, builtin)
+            }
+            _ => format!("{}: builtin", name),
+        },
+        Value::Array(def) if value.is_callable() => {
+            let args: Vec<String> = match &def[0] {
+                Value::Array(names) => names
+                    .iter()
+                    .map(|n| match n {
+                        Value::Quote(q) => q.as_identifier().to_string(),
+                        _ => format!("{:?}", n),
+                    })
+                    .collect(),
+                _ => vec![],
+            };
+
+            match def.get(2) {
+                Some(Value::String(doc)) => {
+                    format!(
+                        "{}: function [{}]\n  {}",
+                        name,
+                        args.join(" "),
+                        doc
+                    )
+                }
+                _ => format!("{}: function [{}]", name, args.join(" ")),
+            }
+        }
+        Value::Composed(..) | Value::Partial { .. } => {
+            format!("{}: function", name)
+        }
+        Value::StructType { fields, .. } => {
+            format!("{}: struct [{}]", name, fields.join(" "))
+        }
+        other => format!("{}: {:?}", name, other),
+    }
+}
+
+/// `(describe)` lists every builtin followed by everything that's been
+/// defined since, and `(describe "name")` describes just that name.
+pub fn lcore_describe(
+    args: &mut Value,
+    symbol_table: &mut Environment,
+) -> Result<Value, LCoreError> {
+    let name = match args.as_array().first() {
+        None => None,
+        Some(Value::String(s)) => Some(s.clone()),
+        Some(Value::Quote(q)) if matches!(**q, Value::Identifier(..)) => {
+            Some(q.as_identifier().to_string())
+        }
+        Some(other) => {
+            return Err(LCoreError::ArgumentError(format!(
+                "ArgumentError: \"describe\" takes a name, got {:?}",
+                other
+            )))
+        }
+    };
+
+    if let Some(name) = name {
+        return match symbol_table.get(name.clone()) {
+            Some(value) => {
+                let description = lcore_describe_value(&name, value);
+                writeln!(symbol_table.output(), "{}", description).ok();
+                Ok(Value::Null)
+            }
+            None => Err(LCoreError::NameError(format!(
+                "NameError: Cannot lookup name: \"{}\"",
+                name
+            ))),
+        };
+    }
+
+    let builtins = lcore_builtins();
+    let mut bindings: Vec<(String, Value)> =
+        symbol_table.bindings().into_iter().collect();
+
+    // Builtins first, then user definitions, alphabetically within each
+    bindings.sort_by_key(|(name, value)| {
+        let builtin =
+            builtins.contains_key(name) && matches!(value, Value::Func { .. });
+        (!builtin, name.clone())
+    });
+
+    for (name, value) in bindings {
+        let description = lcore_describe_value(&name, &value);
+        writeln!(symbol_table.output(), "{}", description).ok();
+    }
+
+    Ok(Value::Null)
+}
 
-    let obj = args
-        .next()
-        .expect("Not enough arguments on call to \"get\": 0/2");
-    let mut key = args
-        .next()
-        .expect("Not enough arguments on call to \"get\": 1/2");
+pub fn lcore_get(
+    args: &mut Value,
+    symbol_table: &mut Environment,
+) -> Result<Value, LCoreError> {
+    let [obj, mut key] = lcore_take_args(args, "get")?;
 
     if let Value::Quote(q) = key {
         key = q;
@@ -302,8 +738,8 @@ pub fn lcore_get(
                     // "Index out of bounds: got {} but len is {}", index,
                     // v.len()));
 
-                    return Err(LCoreError::ArgumentError(format!(
-                        "Index out of bounds: got {} but len is {}",
+                    return Err(LCoreError::IndexError(format!(
+                        "IndexError: Index out of bounds: got {} but len is {}",
                         index,
                         v.len()
                     )));
@@ -363,6 +799,27 @@ pub fn lcore_get(
             _ => unreachable!(),
         },
 
+        Value::Bytes(v) => match key {
+            Value::Int(index) => {
+                let len = v.len() as i64;
+                let idx = if *index < 0 { len + index } else { *index };
+                if idx < 0 || idx >= len {
+                    return Err(LCoreError::IndexError(format!(
+                        "IndexError: Index out of bounds: got {} but len is {}",
+                        index, len
+                    )));
+                }
+                return Ok(Value::Int(v[idx as usize] as i64));
+            }
+
+            _ => {
+                return Err(LCoreError::ArgumentError(format!(
+                    "ArgumentError: Cannot index Bytes with {:?}",
+                    key
+                )))
+            }
+        },
+
         Value::String(v) => match key {
             Value::Int(a) => {
                 println!("*******************************************");
@@ -420,589 +877,4166 @@ pub fn lcore_import(
     let mut args = args.as_array().iter();
     let filename = match args.next() {
         Some(e) => e,
-        None => {
-            return Err(LCoreError::ArgumentError(format!(
-            "ArgumentError: Not enough arguments on call to \"import\": 0/1"
-        )))
-        }
+        None => return Err(lcore_arity_error("import", 1, 0)),
     };
 
-    if let Value::String(file) = filename {
-        symbol_table.extend(lcore_import_file(file.to_string()));
+    let file = match filename {
+        Value::String(file) => file,
+        _ => return Ok(Value::Null),
+    };
+
+    let bindings = lcore_import_module(file.to_string())?;
+
+    // The module's environment also holds every builtin, so leave those out
+    // of what the module is considered to define.
+    let builtins = lcore_builtins();
+    let defined: Vec<(String, Value)> = bindings
+        .iter()
+        .filter(|(name, value)| {
+            !(builtins.contains_key(*name)
+                && matches!(value, Value::Func { .. }))
+        })
+        .map(|(name, value)| (name.clone(), value.clone()))
+        .collect();
+
+    // `(import "file.lcore" 'm)` keeps the module's own bindings in a
+    // separate namespace instead of merging them in directly. They are
+    // referenced as `m/name`, which never collides with the importer's names.
+    // Importing another module under the same alias replaces the namespace.
+    match args.next() {
+        None => symbol_table.extend(bindings),
+        Some(Value::Quote(q)) if matches!(**q, Value::Identifier(..)) => {
+            symbol_table.add_namespace(
+                q.as_identifier().to_string(),
+                defined.iter().cloned().collect(),
+            );
+        }
+        Some(other) => {
+            return Err(LCoreError::ArgumentError(format!(
+                "ArgumentError: \"import\" alias must be quoted, got {:?}",
+                other
+            )))
+        }
     }
 
-    Ok(Value::Null)
+    // Return what the module defined so callers can inspect it or pick out
+    // names themselves.
+    Ok(Value::Dict(
+        defined
+            .into_iter()
+            .map(|(name, value)| (Value::String(name), value))
+            .collect(),
+    ))
 }
 
-pub fn lcore_swap(
-    args: &mut Value,
-    symbol_table: &mut Environment,
-) -> Result<Value, LCoreError> {
-    let mut args = args.as_array().iter();
-    let obj_id = args.next().unwrap().as_value().as_identifier();
-    let index = args.next().unwrap();
-    let value = args.next().unwrap();
-
-    // TODO(pebaz): The `index` is a quoted list of values to index by:
-    // a[b][c][d][e]
-
-    // println!("{}, {:?}, {:?}", obj_id, index, value);
-
-    if let Some(obj) = symbol_table.get(obj_id.to_string()) {
-        let mut current_obj = obj;
-
-        let indexers = index.as_value().as_array();
-        for i in 0..indexers.len() - 1 {
-            // for indexer in index.as_value().as_array() {
-            let indexer = &indexers[i];
-
-            match current_obj {
-                Value::Dict(ref mut v) => {
-                    // current_obj = v[indexer]
-                    if let Value::Identifier(s) = indexer {
-                        current_obj =
-                            v.get_mut(&Value::String(s.to_string())).unwrap();
-                    } else {
-                        current_obj = v.get_mut(&indexer).unwrap();
-                    }
-                }
-
-                Value::Array(ref mut v) => {
-                    // current_obj = v[indexer]
-
-                    if let Value::Int(i) = indexer {
-                        if *i > v.len() as i64 {
-                            return Err(LCoreError::ArgumentError(format!(
-                                "Index out of bounds: got {} but len is {}",
-                                i,
-                                v.len()
-                            )));
-                        } else {
-                            let len = v.len() as i64;
-                            let mut idx = i % len;
-                            if idx < 0 {
-                                idx += len
-                            }
-                            current_obj = v.get_mut(idx as usize).unwrap();
-                        }
-                    } else {
-                        return Err(LCoreError::IndexError(
-                            "IndexError: Cannot index array with non-int"
-                                .to_string(),
-                        ));
-                    }
-                }
+/// Find the slot inside a Dict, Array, or Struct that `indexer` refers to.
+/// Identifiers index Dicts by their String name, like in `dict`.
+fn lcore_index_mut<'a>(
+    obj: &'a mut Value,
+    indexer: &Value,
+) -> Result<&'a mut Value, LCoreError> {
+    match obj {
+        Value::Dict(ref mut v) => {
+            let key = match indexer {
+                Value::Identifier(s) => Value::String(s.to_string()),
+                _ => indexer.clone(),
+            };
 
-                _ => unreachable!(),
+            match v.get_mut(&key) {
+                Some(e) => Ok(e),
+                None => Err(LCoreError::IndexError(format!(
+                    "IndexError: No {} key in Dict",
+                    lcore_value_to_string(&key, true)
+                ))),
             }
         }
 
-        let indexer = &indexers[indexers.len() - 1];
-        match current_obj {
-            Value::Dict(ref mut v) => {
-                if let Value::Identifier(s) = indexer {
-                    *v.get_mut(&Value::String(s.to_string())).unwrap() =
-                        value.clone();
-                } else {
-                    *v.get_mut(&indexer).unwrap() = value.clone();
+        Value::Array(ref mut v) => {
+            if let Value::Int(i) = indexer {
+                let len = v.len() as i64;
+                if *i < -len || *i >= len {
+                    return Err(LCoreError::IndexError(format!(
+                        "IndexError: Index out of bounds: got {} but len is {}",
+                        i, len
+                    )));
                 }
-            }
 
-            Value::Array(ref mut v) => {
-                if let Value::Int(i) = indexer {
-                    if *i > v.len() as i64 {
-                        return Err(LCoreError::ArgumentError(format!(
-                            "Index out of bounds: got {} but len is {}",
-                            i,
-                            v.len()
-                        )));
-                    } else {
-                        let len = v.len() as i64;
-                        let mut idx = i % len;
-                        if idx < 0 {
-                            idx += len
-                        }
-                        *v.get_mut(idx as usize).unwrap() = value.clone();
-                    }
-                } else {
-                    return Err(LCoreError::IndexError(
-                        "IndexError: Cannot index array with non-int"
-                            .to_string(),
-                    ));
-                }
+                let idx = if *i < 0 { len + i } else { *i };
+                Ok(v.get_mut(idx as usize).unwrap())
+            } else {
+                Err(LCoreError::IndexError(
+                    "IndexError: Cannot index array with non-int".to_string(),
+                ))
             }
+        }
 
-            _ => unreachable!(),
+        Value::Struct { ref mut fields, .. } => {
+            lcore_struct_field(fields, indexer)
         }
 
-        // lcore_print_value(
-        // 	&mut Value::Array(vec![
-        // 		symbol_table.get(obj_id.to_string()).unwrap().clone()
-        // 	])
-        // );
+        _ => Err(LCoreError::ArgumentError(format!(
+            "ArgumentError: Cannot index {:?}",
+            obj
+        ))),
     }
+}
 
-    Ok(Value::Null)
+/// Walk an index path (`a[b][c][d]`) into nested Dicts, Arrays, and Structs
+/// and return the slot at the end of it.
+fn lcore_walk_path<'a>(
+    obj: &'a mut Value,
+    path: &[Value],
+) -> Result<&'a mut Value, LCoreError> {
+    let mut current_obj = obj;
+
+    for indexer in path {
+        current_obj = lcore_index_mut(current_obj, indexer)?;
+    }
+
+    Ok(current_obj)
 }
 
-pub fn lcore_len(
+fn lcore_check_not_frozen(
+    name: &str,
+    symbol_table: &Environment,
+) -> Result<(), LCoreError> {
+    if symbol_table.is_frozen(name) {
+        return Err(LCoreError::ArgumentError(format!(
+            "ArgumentError: Cannot mutate frozen value \"{}\"",
+            name
+        )));
+    }
+    Ok(())
+}
+
+pub fn lcore_swap(
     args: &mut Value,
     symbol_table: &mut Environment,
 ) -> Result<Value, LCoreError> {
-    let mut args = args.as_array().iter();
-    let arg = match args.next() {
-        Some(e) => e,
-        None => {
-            return Err(LCoreError::ArgumentError(format!(
-                "ArgumentError: Not enough arguments on call to \"len\": 0/1"
-            )))
-        }
-    };
+    let [obj_id, index, value] = lcore_take_args(args, "swap")?;
+    let obj_id = obj_id.as_value().as_identifier();
 
-    return match arg {
-        Value::Array(v) => Ok(Value::Int(v.len() as i64)),
-        Value::Dict(v) => Ok(Value::Int(v.len() as i64)),
-        Value::String(v) => Ok(Value::Int(v.len() as i64)),
-        Value::Quote(v) => Ok(Value::Int(1)),
-        _ => Err(LCoreError::ArgumentError(format!(
-            "ArgumentError: {:?} has no length",
-            arg
-        ))),
-    };
+    // NOTE: The `index` is a quoted list of values to index by:
+    // a[b][c][d][e]
+
+    lcore_check_not_frozen(obj_id, symbol_table)?;
+    if let Some(obj) = symbol_table.get(obj_id.to_string()) {
+        *lcore_walk_path(obj, index.as_value().as_array())? = value.clone();
+    }
+
+    Ok(Value::Null)
 }
 
-pub fn lcore_equals(
+/// Update a slot inside a variable in place by calling `func` on its current
+/// value: `(update-in! 'counts '[hits] inc)`.
+pub fn lcore_update_in(
     args: &mut Value,
     symbol_table: &mut Environment,
 ) -> Result<Value, LCoreError> {
-    let mut args = args.as_array().iter();
-    let a = args.next().unwrap();
-    let b = args.next().unwrap();
+    let args = args.as_array();
 
-    match (a, b) {
-        (Value::Null, Value::Null) => Ok(Value::Boolean(true)),
-        (Value::Int(a), Value::Int(b)) => Ok(Value::Boolean(a == b)),
-        (Value::Float(a), Value::Float(b)) => Ok(Value::Boolean(a == b)),
-        (Value::String(a), Value::String(b)) => Ok(Value::Boolean(a == b)),
-        (Value::Boolean(a), Value::Boolean(b)) => Ok(Value::Boolean(a == b)),
-        (Value::Identifier(a), Value::Identifier(b)) => {
-            Ok(Value::Boolean(a == b))
+    let (name, path, func) = match (args.first(), args.get(1), args.get(2)) {
+        (Some(Value::Quote(name)), Some(path), Some(func)) => match &**name {
+            Value::Identifier(name) => (name, lcore_index_path(path)?, func),
+            _ => {
+                return Err(LCoreError::ArgumentError(format!(
+                    "ArgumentError: \"update-in!\" takes a quoted name, a path and a function"
+                )))
+            }
+        },
+        _ => {
+            return Err(LCoreError::ArgumentError(format!(
+                "ArgumentError: \"update-in!\" takes a quoted name, a path and a function"
+            )))
         }
-        (Value::Dict(a), Value::Dict(b)) => Ok(Value::Boolean(a == b)),
-        (Value::Array(a), Value::Array(b)) => Ok(Value::Boolean(a == b)),
-        (Value::Quote(a), Value::Quote(b)) => lcore_equals(
-            &mut Value::Array(vec![*a.clone(), *b.clone()]),
-            symbol_table,
-        ),
+    };
 
-        (Value::Func { f: a }, Value::Func { f: b }) => {
-            // TODO(pebaz): Allow comparing builtin funcs?
-            let addr_a = format!("{:p}", &*a);
-            let addr_b = format!("{:p}", &*b);
-            println!("HERE: {}, {}", addr_a, addr_b);
-            Ok(Value::Boolean(addr_a == addr_b))
+    lcore_check_callable(func)?;
+    lcore_check_not_frozen(name, symbol_table)?;
+
+    let current = match symbol_table.get(name.clone()) {
+        Some(obj) => lcore_walk_path(obj, path)?.clone(),
+        None => {
+            return Err(LCoreError::NameError(format!(
+                "NameError: Cannot lookup name: \"{}\"",
+                name
+            )))
         }
+    };
+
+    let updated =
+        lcore_call(func, &mut Value::Array(vec![current]), symbol_table)?;
+
+    if let Some(obj) = symbol_table.get(name.clone()) {
+        *lcore_walk_path(obj, path)? = updated.clone();
+    }
+
+    Ok(updated)
+}
+
+/// Read an index path argument, which may be quoted: `[1 0]` or `'[a b]`.
+fn lcore_index_path(path: &Value) -> Result<&Vec<Value>, LCoreError> {
+    let path = match path {
+        Value::Quote(q) => &**q,
+        other => other,
+    };
 
+    match path {
+        Value::Array(p) => Ok(p),
         _ => Err(LCoreError::ArgumentError(format!(
-            "ArgumentError: Type mismatch ({:?} and {:?})",
-            a, b
+            "ArgumentError: Path must be an Array ({:?})",
+            path
         ))),
     }
 }
 
-pub fn lcore_not_equals(
-    args: &mut Value,
-    symbol_table: &mut Environment,
-) -> Result<Value, LCoreError> {
-    let mut args = args.as_array().iter();
-    let a = args.next().unwrap();
-    let b = args.next().unwrap();
+fn lcore_assoc_path(args: &Value, fn_name: &str) -> Result<Value, LCoreError> {
+    let args = args.as_array();
 
-    match (a, b) {
-        (Value::Null, Value::Null) => Ok(Value::Boolean(false)),
-        (Value::Int(a), Value::Int(b)) => Ok(Value::Boolean(a != b)),
-        (Value::Float(a), Value::Float(b)) => Ok(Value::Boolean(a != b)),
-        (Value::String(a), Value::String(b)) => Ok(Value::Boolean(a != b)),
-        (Value::Boolean(a), Value::Boolean(b)) => Ok(Value::Boolean(a != b)),
-        (Value::Identifier(a), Value::Identifier(b)) => {
-            Ok(Value::Boolean(a != b))
-        }
-        (Value::Dict(a), Value::Dict(b)) => Ok(Value::Boolean(a != b)),
-        (Value::Array(a), Value::Array(b)) => Ok(Value::Boolean(a != b)),
-        (Value::Quote(a), Value::Quote(b)) => lcore_not_equals(
-            &mut Value::Array(vec![*a.clone(), *b.clone()]),
-            symbol_table,
-        ),
+    if args.len() != 3 {
+        return Err(lcore_arity_error(fn_name, 3, args.len()));
+    }
 
-        (Value::Func { f: a }, Value::Func { f: b }) => {
-            // TODO(pebaz): Allow comparing builtin funcs?
-            let addr_a = format!("{:p}", &*a);
-            let addr_b = format!("{:p}", &*b);
-            println!("HERE: {}, {}", addr_a, addr_b);
-            Ok(Value::Boolean(addr_a != addr_b))
-        }
+    let path = lcore_index_path(&args[1])?;
 
-        _ => Err(LCoreError::ArgumentError(format!(
-            "ArgumentError: Type mismatch ({:?} and {:?})",
-            a, b
-        ))),
-    }
+    let mut result = args[0].clone();
+    *lcore_walk_path(&mut result, path)? = args[2].clone();
+
+    Ok(result)
 }
 
-pub fn lcore_less_than(
+/// Like `swap`, but returns an updated copy of any collection instead of
+/// mutating a variable: `(set-in [[1 2] [3 4]] [1 0] 5)`.
+pub fn lcore_set_in(
     args: &mut Value,
     symbol_table: &mut Environment,
 ) -> Result<Value, LCoreError> {
-    let mut args = args.as_array().iter();
-    let a = args.next().unwrap();
-    let b = args.next().unwrap();
-
-    match (a, b) {
-        (Value::Int(a), Value::Int(b)) => Ok(Value::Boolean(a < b)),
-        (Value::Float(a), Value::Float(b)) => Ok(Value::Boolean(a < b)),
-        (Value::String(a), Value::String(b)) => {
-            Ok(Value::Boolean(a.len() < b.len()))
-        }
-        (Value::Identifier(a), Value::Identifier(b)) => {
-            Ok(Value::Boolean(a.len() < b.len()))
-        }
-        (Value::Dict(a), Value::Dict(b)) => {
-            Ok(Value::Boolean(a.len() < b.len()))
-        }
-        (Value::Array(a), Value::Array(b)) => {
-            Ok(Value::Boolean(a.len() < b.len()))
-        }
-        (Value::Quote(a), Value::Quote(b)) => lcore_less_than(
-            &mut Value::Array(vec![*a.clone(), *b.clone()]),
-            symbol_table,
-        ),
-
-        _ => Err(LCoreError::ArgumentError(format!(
-            "ArgumentError: Invalid Argument Types ({:?} and {:?})",
-            a, b
-        ))),
-    }
+    lcore_assoc_path(args, "set-in")
 }
 
-pub fn lcore_logical_or(
+/// Same as `set-in`, named to pair with `get-in`.
+pub fn lcore_assoc_in(
     args: &mut Value,
     symbol_table: &mut Environment,
 ) -> Result<Value, LCoreError> {
-    let mut args = args.as_array().iter();
-    let a = args.next().unwrap();
-    let b = args.next().unwrap();
+    lcore_assoc_path(args, "assoc-in")
+}
 
-    match (a, b) {
-        (Value::Boolean(a), Value::Boolean(b)) => Ok(Value::Boolean(a | b)),
+/// Follow an index path into nested collections and return the value at
+/// the end of it: `(get-in config '[db ports 0])`.
+pub fn lcore_get_in(
+    args: &mut Value,
+    symbol_table: &mut Environment,
+) -> Result<Value, LCoreError> {
+    let args = args.as_array();
 
-        _ => Err(LCoreError::ArgumentError(format!(
-            "ArgumentError: Not booleans ({:?} and {:?})",
-            a, b
-        ))),
+    if args.len() != 2 {
+        return Err(lcore_arity_error("get-in", 2, args.len()));
     }
+
+    let path = lcore_index_path(&args[1])?;
+
+    let mut obj = args[0].clone();
+    Ok(lcore_walk_path(&mut obj, path)?.clone())
 }
 
-pub fn lcore_logical_and(
+/// Look up a slash-separated path like `"servers/0/name"` through nested
+/// Dicts and Arrays. Numeric segments index Arrays (negative ones count from
+/// the end) and Dicts are looked up by String key, falling back to an Int
+/// key. Unlike `get-in`, a missing path gives Null instead of an error.
+pub fn lcore_get_path(
     args: &mut Value,
     symbol_table: &mut Environment,
 ) -> Result<Value, LCoreError> {
-    let mut args = args.as_array().iter();
-    let a = args.next().unwrap();
-    let b = args.next().unwrap();
+    let args = args.as_array();
 
-    match (a, b) {
-        (Value::Boolean(a), Value::Boolean(b)) => Ok(Value::Boolean(a & b)),
+    let (obj, path) = match (args.first(), args.get(1)) {
+        (Some(obj), Some(Value::String(path))) => (obj, path),
+        _ => {
+            return Err(LCoreError::ArgumentError(format!(
+                "ArgumentError: \"get-path\" takes a value and a path String"
+            )))
+        }
+    };
 
-        _ => Err(LCoreError::ArgumentError(format!(
-            "ArgumentError: Not booleans ({:?} and {:?})",
-            a, b
-        ))),
+    let path = path.strip_prefix('/').unwrap_or(path);
+    let mut current = obj;
+    for segment in path.split('/').filter(|s| !s.is_empty()) {
+        let index = segment.parse::<i64>().ok();
+
+        let next = match (current, index) {
+            (Value::Array(v), Some(i)) => {
+                let len = v.len() as i64;
+                let i = if i < 0 { len + i } else { i };
+                if i < 0 {
+                    None
+                } else {
+                    v.get(i as usize)
+                }
+            }
+            (Value::Dict(d), _) => d
+                .get(&Value::String(segment.to_string()))
+                .or_else(|| index.and_then(|i| d.get(&Value::Int(i)))),
+            _ => None,
+        };
+
+        match next {
+            Some(value) => current = value,
+            None => return Ok(Value::Null),
+        }
     }
+
+    Ok(current.clone())
 }
 
-pub fn lcore_logical_not(
+/// Define a struct type with named fields. The type is bound to `name` and
+/// constructs new instances when called: `(defstruct 'Point '[x y])` then
+/// `(Point 1 2)`.
+pub fn lcore_defstruct(
     args: &mut Value,
     symbol_table: &mut Environment,
 ) -> Result<Value, LCoreError> {
-    let mut args = args.as_array().iter();
-    let a = args.next().unwrap();
+    let args = args.as_array();
 
-    if let Value::Boolean(b) = a {
-        return Ok(Value::Boolean(!b));
-    } else {
+    if args.len() != 2 {
+        return Err(lcore_arity_error("defstruct", 2, args.len()));
+    }
+
+    let name = match &args[0] {
+        Value::Quote(q) => match &**q {
+            Value::Identifier(s) => s.clone(),
+            _ => String::new(),
+        },
+        _ => String::new(),
+    };
+
+    if name.is_empty() {
         return Err(LCoreError::ArgumentError(format!(
-            "ArgumentError: Not a boolean ({:?})",
-            a
+            "ArgumentError: Struct name must be a quoted Identifier"
         )));
     }
+
+    let field_list = match &args[1] {
+        Value::Quote(q) => match &**q {
+            Value::Array(a) => a,
+            _ => {
+                return Err(LCoreError::ArgumentError(format!(
+                    "ArgumentError: Struct fields must be a quoted Array"
+                )))
+            }
+        },
+        _ => {
+            return Err(LCoreError::ArgumentError(format!(
+                "ArgumentError: Struct fields must be a quoted Array"
+            )))
+        }
+    };
+
+    let mut fields = Vec::with_capacity(field_list.len());
+    for field in field_list {
+        match field {
+            Value::Identifier(f) => fields.push(f.clone()),
+            _ => {
+                return Err(LCoreError::ArgumentError(format!(
+                    "ArgumentError: Struct field names must be Identifiers"
+                )))
+            }
+        }
+    }
+
+    symbol_table.insert(name.clone(), Value::StructType { name, fields });
+
+    Ok(Value::Null)
 }
 
-pub fn lcore_to_str(
-    args: &mut Value,
-    symbol_table: &mut Environment,
-) -> Result<Value, LCoreError> {
-    Ok(Value::String(String::from("LambdaCore String!")))
+/// Find the slot holding a struct field given a quoted Identifier or String.
+fn lcore_struct_field<'a>(
+    fields: &'a mut [(String, Value)],
+    field: &Value,
+) -> Result<&'a mut Value, LCoreError> {
+    let name = match field {
+        Value::Quote(q) => match &**q {
+            Value::Identifier(s) => s,
+            _ => "",
+        },
+        Value::Identifier(s) => s,
+        Value::String(s) => s,
+        _ => "",
+    };
+
+    match fields.iter_mut().find(|(f, _)| f == name) {
+        Some((_, value)) => Ok(value),
+        None => Err(LCoreError::NameError(format!(
+            "NameError: No field named: {:?}",
+            name
+        ))),
+    }
 }
 
-pub fn lcore_add(
+pub fn lcore_get_field(
     args: &mut Value,
     symbol_table: &mut Environment,
 ) -> Result<Value, LCoreError> {
-    let mut args = args.as_array().iter();
-    let a = args.next().unwrap();
-    let b = args.next().unwrap();
+    let args = args.as_array();
 
-    match (a, b) {
-        (Value::Int(a), Value::Int(b)) => Ok(Value::Int(a + b)),
-        (Value::Float(a), Value::Float(b)) => Ok(Value::Float(a + b)),
-        (Value::String(a), Value::String(b)) => {
-            let mut result = a.clone();
-            result.push_str(b);
-            Ok(Value::String(result.to_string()))
-        }
-        (Value::Array(a), Value::Array(b)) => {
-            let mut result = a.clone();
-            result.extend(b.iter().cloned());
-            Ok(Value::Array(result))
-        }
+    if args.len() != 2 {
+        return Err(lcore_arity_error("get-field", 2, args.len()));
+    }
 
-        _ => Err(LCoreError::ArgumentError(format!(
-            "ArgumentError: Invalid operands ({:?} and {:?})",
-            a, b
+    match args[0].clone() {
+        Value::Struct { mut fields, .. } => {
+            Ok(lcore_struct_field(&mut fields, &args[1])?.clone())
+        }
+        other => Err(LCoreError::ArgumentError(format!(
+            "ArgumentError: {:?} is not a Struct",
+            other
         ))),
     }
 }
 
-pub fn lcore_sub(
+/// Return a copy of the struct with one field updated. Use `swap` to update
+/// a struct stored in a variable in place.
+pub fn lcore_set_field(
     args: &mut Value,
     symbol_table: &mut Environment,
 ) -> Result<Value, LCoreError> {
-    let mut args = args.as_array().iter();
-    let a = args.next().unwrap();
-    let b = args.next().unwrap();
+    let args = args.as_array();
 
-    match (a, b) {
-        (Value::Int(a), Value::Int(b)) => Ok(Value::Int(a - b)),
-        (Value::Float(a), Value::Float(b)) => Ok(Value::Float(a - b)),
+    if args.len() != 3 {
+        return Err(lcore_arity_error("set-field", 3, args.len()));
+    }
 
-        _ => Err(LCoreError::ArgumentError(format!(
-            "ArgumentError: Invalid operands ({:?} and {:?})",
-            a, b
-        ))),
+    let mut result = args[0].clone();
+    match result {
+        Value::Struct { ref mut fields, .. } => {
+            *lcore_struct_field(fields, &args[1])? = args[2].clone();
+        }
+        _ => {
+            return Err(LCoreError::ArgumentError(format!(
+                "ArgumentError: {:?} is not a Struct",
+                args[0]
+            )))
+        }
     }
+
+    Ok(result)
 }
 
-pub fn lcore_mul(
+/// Split an Array into Arrays of `size` elements. The last chunk holds any
+/// leftover elements.
+pub fn lcore_chunk(
     args: &mut Value,
     symbol_table: &mut Environment,
 ) -> Result<Value, LCoreError> {
-    let mut args = args.as_array().iter();
-    let a = args.next().unwrap();
-    let b = args.next().unwrap();
+    let args = args.as_array();
 
-    match (a, b) {
-        (Value::Int(a), Value::Int(b)) => Ok(Value::Int(a * b)),
-        (Value::Float(a), Value::Float(b)) => Ok(Value::Float(a * b)),
-        (Value::String(a), Value::Int(b)) => {
-            let mut result = a.clone();
-            for i in 0..*b - 1 {
-                result.push_str(a);
-            }
-            Ok(Value::String(result.to_string()))
-        }
-        (Value::Array(a), Value::Int(b)) => {
-            let aa = a.clone();
-            let mut result = a.clone();
-            for i in 0..*b - 1 {
-                result.extend(aa.iter().cloned());
+    match (args.first(), args.get(1)) {
+        (Some(Value::Array(v)), Some(Value::Int(size))) => {
+            if *size <= 0 {
+                return Err(LCoreError::ArgumentError(format!(
+                    "ArgumentError: Chunk size must be positive ({})",
+                    size
+                )));
             }
-            Ok(Value::Array(result))
+
+            Ok(Value::Array(
+                v.chunks(*size as usize)
+                    .map(|c| Value::Array(c.to_vec()))
+                    .collect(),
+            ))
         }
 
         _ => Err(LCoreError::ArgumentError(format!(
-            "ArgumentError: Invalid operands ({:?} and {:?})",
-            a, b
+            "ArgumentError: \"chunk\" takes an Array and an Int"
         ))),
     }
 }
 
-pub fn lcore_div(
+/// Take one element from each Array in turn, stopping as soon as the
+/// shortest Array runs out.
+pub fn lcore_interleave(
     args: &mut Value,
     symbol_table: &mut Environment,
 ) -> Result<Value, LCoreError> {
-    let mut args = args.as_array().iter();
-    let a = args.next().unwrap();
-    let b = args.next().unwrap();
-
-    match (a, b) {
-        (Value::Int(a), Value::Int(b)) => Ok(Value::Int(a / b)),
-        (Value::Float(a), Value::Float(b)) => Ok(Value::Float(a / b)),
+    let args = args.as_array();
 
-        _ => Err(LCoreError::ArgumentError(format!(
-            "ArgumentError: Invalid operands ({:?} and {:?})",
+    let mut arrays = Vec::new();
+    for arg in args {
+        match arg {
+            Value::Array(v) => arrays.push(v),
+            _ => {
+                return Err(LCoreError::ArgumentError(format!(
+                    "ArgumentError: \"interleave\" takes Arrays, got {:?}",
+                    arg
+                )))
+            }
+        }
+    }
+
+    if arrays.len() < 2 {
+        return Err(LCoreError::ArgumentError(format!(
+            "ArgumentError: \"interleave\" takes at least 2 Arrays"
+        )));
+    }
+
+    let shortest = arrays.iter().map(|v| v.len()).min().unwrap_or(0);
+    let mut result = Vec::with_capacity(shortest * arrays.len());
+    for i in 0..shortest {
+        for v in &arrays {
+            result.push(v[i].clone());
+        }
+    }
+
+    Ok(Value::Array(result))
+}
+
+/// Flatten an Array by exactly one level. Nested Arrays are spliced in;
+/// any other element is kept as is.
+pub fn lcore_flatten_1(
+    args: &mut Value,
+    symbol_table: &mut Environment,
+) -> Result<Value, LCoreError> {
+    match args.as_array().first() {
+        Some(Value::Array(v)) => {
+            let mut result = Vec::new();
+            for value in v {
+                match value {
+                    Value::Array(inner) => {
+                        result.extend(inner.iter().cloned())
+                    }
+                    other => result.push(other.clone()),
+                }
+            }
+            Ok(Value::Array(result))
+        }
+        _ => Err(LCoreError::ArgumentError(format!(
+            "ArgumentError: \"flatten-1\" takes an Array"
+        ))),
+    }
+}
+
+/// Find the position of the first element equal to `value`, optionally
+/// starting the search at `start`. Returns -1 if there is no such element.
+pub fn lcore_array_index_of(
+    args: &mut Value,
+    symbol_table: &mut Environment,
+) -> Result<Value, LCoreError> {
+    let args = args.as_array();
+
+    let (v, value) = match (args.first(), args.get(1)) {
+        (Some(Value::Array(v)), Some(value)) => (v, value),
+        _ => {
+            return Err(LCoreError::ArgumentError(format!(
+                "ArgumentError: \"index-of\" takes an Array and a value"
+            )))
+        }
+    };
+
+    let start = match args.get(2) {
+        None => 0,
+        Some(Value::Int(i)) if *i >= 0 => *i as usize,
+        Some(other) => {
+            return Err(LCoreError::ArgumentError(format!(
+                "ArgumentError: Start index must be a non-negative Int ({:?})",
+                other
+            )))
+        }
+    };
+
+    let index = v
+        .iter()
+        .enumerate()
+        .skip(start)
+        .find(|(_, element)| *element == value)
+        .map_or(-1, |(i, _)| i as i64);
+
+    Ok(Value::Int(index))
+}
+
+/// Membership test for any collection: a substring (or Char) of a String, an
+/// element of an Array, or a key of a Dict.
+pub fn lcore_contains_q(
+    args: &mut Value,
+    symbol_table: &mut Environment,
+) -> Result<Value, LCoreError> {
+    let args = args.as_array();
+
+    let found = match (args.first(), args.get(1)) {
+        (Some(Value::String(s)), Some(Value::String(sub))) => s.contains(&**sub),
+        (Some(Value::String(s)), Some(Value::Char(c))) => s.contains(*c),
+        (Some(Value::Array(v)), Some(value)) => v.contains(value),
+        (Some(Value::Dict(d)), Some(key)) => match key {
+            Value::Quote(q) => match &**q {
+                Value::Identifier(s) => {
+                    d.contains_key(&Value::String(s.clone()))
+                }
+                other => d.contains_key(other),
+            },
+            _ => d.contains_key(key),
+        },
+        _ => {
+            return Err(LCoreError::ArgumentError(format!(
+                "ArgumentError: \"contains?\" takes a String, Array or Dict and a value"
+            )))
+        }
+    };
+
+    Ok(Value::Boolean(found))
+}
+
+fn lcore_take_n(
+    args: &Value,
+    fn_name: &str,
+    from_end: bool,
+) -> Result<Value, LCoreError> {
+    let args = args.as_array();
+
+    match (args.first(), args.get(1)) {
+        (Some(Value::Array(v)), Some(Value::Int(n))) => {
+            let n = (*n).clamp(0, v.len() as i64) as usize;
+            let taken = if from_end { &v[v.len() - n..] } else { &v[..n] };
+            Ok(Value::Array(taken.to_vec()))
+        }
+        _ => Err(LCoreError::ArgumentError(format!(
+            "ArgumentError: \"{}\" takes an Array and an Int",
+            fn_name
+        ))),
+    }
+}
+
+/// The first `n` elements of an Array, or all of them if there are fewer.
+pub fn lcore_first_n(
+    args: &mut Value,
+    symbol_table: &mut Environment,
+) -> Result<Value, LCoreError> {
+    lcore_take_n(args, "first-n", false)
+}
+
+/// The last `n` elements of an Array, or all of them if there are fewer.
+pub fn lcore_last_n(
+    args: &mut Value,
+    symbol_table: &mut Environment,
+) -> Result<Value, LCoreError> {
+    lcore_take_n(args, "last-n", true)
+}
+
+/// Take every `step`-th element of an Array, starting with the first.
+pub fn lcore_every_nth(
+    args: &mut Value,
+    symbol_table: &mut Environment,
+) -> Result<Value, LCoreError> {
+    let args = args.as_array();
+
+    match (args.first(), args.get(1)) {
+        (Some(Value::Array(v)), Some(Value::Int(step))) => {
+            if *step <= 0 {
+                return Err(LCoreError::ArgumentError(format!(
+                    "ArgumentError: Step must be positive ({})",
+                    step
+                )));
+            }
+
+            Ok(Value::Array(
+                v.iter().step_by(*step as usize).cloned().collect(),
+            ))
+        }
+
+        _ => Err(LCoreError::ArgumentError(format!(
+            "ArgumentError: \"every-nth\" takes an Array and an Int"
+        ))),
+    }
+}
+
+/// Rotate an Array left by `shift` places, or right if `shift` is negative.
+/// Shifts wrap around, so any Int is allowed.
+pub fn lcore_rotate(
+    args: &mut Value,
+    symbol_table: &mut Environment,
+) -> Result<Value, LCoreError> {
+    let args = args.as_array();
+
+    match (args.first(), args.get(1)) {
+        (Some(Value::Array(v)), Some(Value::Int(shift))) => {
+            let mut rotated = v.clone();
+            if !rotated.is_empty() {
+                let len = rotated.len() as i64;
+                rotated.rotate_left(shift.rem_euclid(len) as usize);
+            }
+            Ok(Value::Array(rotated))
+        }
+
+        _ => Err(LCoreError::ArgumentError(format!(
+            "ArgumentError: \"rotate\" takes an Array and an Int"
+        ))),
+    }
+}
+
+/// Split an Array into `[prefix suffix]` at `index`. Negative indices count
+/// from the end like in `get`, and out of range indices are clamped.
+pub fn lcore_split_at(
+    args: &mut Value,
+    symbol_table: &mut Environment,
+) -> Result<Value, LCoreError> {
+    let args = args.as_array();
+
+    match (args.first(), args.get(1)) {
+        (Some(Value::Array(v)), Some(Value::Int(index))) => {
+            let len = v.len() as i64;
+            let index = if *index < 0 { len + index } else { *index };
+            let (prefix, suffix) = v.split_at(index.clamp(0, len) as usize);
+
+            Ok(Value::Array(vec![
+                Value::Array(prefix.to_vec()),
+                Value::Array(suffix.to_vec()),
+            ]))
+        }
+
+        _ => Err(LCoreError::ArgumentError(format!(
+            "ArgumentError: \"split-at\" takes an Array and an Int"
+        ))),
+    }
+}
+
+/// The element of an Array at `index`. Negative indices count from the end
+/// like in `get`, but out of range indices are an IndexError.
+pub fn lcore_nth(
+    args: &mut Value,
+    symbol_table: &mut Environment,
+) -> Result<Value, LCoreError> {
+    let args = args.as_array();
+
+    match (args.first(), args.get(1)) {
+        (Some(Value::Array(v)), Some(Value::Int(index))) => {
+            let len = v.len() as i64;
+            let idx = if *index < 0 { len + index } else { *index };
+            if idx < 0 || idx >= len {
+                return Err(LCoreError::IndexError(format!(
+                    "IndexError: Index out of bounds: got {} but len is {}",
+                    index, len
+                )));
+            }
+            Ok(v[idx as usize].clone())
+        }
+
+        _ => Err(LCoreError::ArgumentError(format!(
+            "ArgumentError: \"nth\" takes an Array and an Int"
+        ))),
+    }
+}
+
+/// The last element of an Array. Empty Arrays are an IndexError.
+pub fn lcore_last(
+    args: &mut Value,
+    symbol_table: &mut Environment,
+) -> Result<Value, LCoreError> {
+    match args.as_array().first() {
+        Some(Value::Array(v)) => match v.last() {
+            Some(last) => Ok(last.clone()),
+            None => Err(LCoreError::IndexError(
+                "IndexError: \"last\" of an empty Array".to_string(),
+            )),
+        },
+
+        _ => Err(LCoreError::ArgumentError(format!(
+            "ArgumentError: \"last\" takes an Array"
+        ))),
+    }
+}
+
+/// A copy of an Array with `value` inserted before `index`. An index equal to
+/// the length appends.
+pub fn lcore_insert(
+    args: &mut Value,
+    symbol_table: &mut Environment,
+) -> Result<Value, LCoreError> {
+    let args = args.as_array();
+
+    match (args.first(), args.get(1), args.get(2)) {
+        (Some(Value::Array(v)), Some(Value::Int(index)), Some(value)) => {
+            if *index < 0 || *index > v.len() as i64 {
+                return Err(LCoreError::IndexError(format!(
+                    "IndexError: Index out of bounds: got {} but len is {}",
+                    index,
+                    v.len()
+                )));
+            }
+
+            let mut inserted = v.clone();
+            inserted.insert(*index as usize, value.clone());
+            Ok(Value::Array(inserted))
+        }
+
+        _ => Err(LCoreError::ArgumentError(format!(
+            "ArgumentError: \"insert\" takes an Array, an Int and a value"
+        ))),
+    }
+}
+
+/// A copy of an Array without the element at `index`.
+pub fn lcore_remove_at(
+    args: &mut Value,
+    symbol_table: &mut Environment,
+) -> Result<Value, LCoreError> {
+    let args = args.as_array();
+
+    match (args.first(), args.get(1)) {
+        (Some(Value::Array(v)), Some(Value::Int(index))) => {
+            if *index < 0 || *index >= v.len() as i64 {
+                return Err(LCoreError::IndexError(format!(
+                    "IndexError: Index out of bounds: got {} but len is {}",
+                    index,
+                    v.len()
+                )));
+            }
+
+            let mut removed = v.clone();
+            removed.remove(*index as usize);
+            Ok(Value::Array(removed))
+        }
+
+        _ => Err(LCoreError::ArgumentError(format!(
+            "ArgumentError: \"remove-at\" takes an Array and an Int"
+        ))),
+    }
+}
+
+/// Split an Array into `[matching non-matching]` using a predicate.
+pub fn lcore_partition(
+    args: &mut Value,
+    symbol_table: &mut Environment,
+) -> Result<Value, LCoreError> {
+    let args = args.as_array();
+
+    let (pred, v) = match (args.first(), args.get(1)) {
+        (Some(pred), Some(Value::Array(v))) => (pred, v),
+        _ => {
+            return Err(LCoreError::ArgumentError(format!(
+                "ArgumentError: \"partition\" takes a function and an Array"
+            )))
+        }
+    };
+
+    lcore_check_callable(pred)?;
+
+    let mut matching = Vec::new();
+    let mut non_matching = Vec::new();
+
+    for value in v {
+        if lcore_predicate(pred, value, symbol_table)? {
+            matching.push(value.clone());
+        } else {
+            non_matching.push(value.clone());
+        }
+    }
+
+    Ok(Value::Array(vec![
+        Value::Array(matching),
+        Value::Array(non_matching),
+    ]))
+}
+
+/// Call `func` on each element of an Array and concatenate the Arrays it
+/// returns: `(flat-map twice [1 2])` with `twice` returning `[x x]`.
+pub fn lcore_flat_map(
+    args: &mut Value,
+    symbol_table: &mut Environment,
+) -> Result<Value, LCoreError> {
+    let args = args.as_array();
+
+    let (func, v) = match (args.first(), args.get(1)) {
+        (Some(func), Some(Value::Array(v))) => (func, v),
+        _ => {
+            return Err(LCoreError::ArgumentError(format!(
+                "ArgumentError: \"flat-map\" takes a function and an Array"
+            )))
+        }
+    };
+
+    lcore_check_callable(func)?;
+
+    let mut result = Vec::new();
+
+    for value in v {
+        match lcore_call(
+            func,
+            &mut Value::Array(vec![value.clone()]),
+            symbol_table,
+        )? {
+            Value::Array(items) => result.extend(items),
+            other => {
+                return Err(LCoreError::ArgumentError(format!(
+                    "ArgumentError: \"flat-map\" function returned {:?}, not an Array",
+                    other
+                )))
+            }
+        }
+    }
+
+    Ok(Value::Array(result))
+}
+
+/// Like a fold, but return every intermediate accumulator, starting with
+/// `init`: `(scan + 0 [1 2 3])` gives `[0 1 3 6]`.
+pub fn lcore_scan(
+    args: &mut Value,
+    symbol_table: &mut Environment,
+) -> Result<Value, LCoreError> {
+    let args = args.as_array();
+
+    let (func, init, v) = match (args.first(), args.get(1), args.get(2)) {
+        (Some(func), Some(init), Some(Value::Array(v))) => (func, init, v),
+        _ => {
+            return Err(LCoreError::ArgumentError(format!(
+                "ArgumentError: \"scan\" takes a function, an initial value and an Array"
+            )))
+        }
+    };
+
+    lcore_check_callable(func)?;
+
+    let mut acc = init.clone();
+    let mut result = Vec::with_capacity(v.len() + 1);
+    result.push(acc.clone());
+
+    for value in v {
+        acc = lcore_call(
+            func,
+            &mut Value::Array(vec![acc, value.clone()]),
+            symbol_table,
+        )?;
+        result.push(acc.clone());
+    }
+
+    Ok(Value::Array(result))
+}
+
+/// The first `n` values of `[init (func init) (func (func init)) ...]`.
+pub fn lcore_iterate(
+    args: &mut Value,
+    symbol_table: &mut Environment,
+) -> Result<Value, LCoreError> {
+    let args = args.as_array();
+
+    let (func, init, n) = match (args.first(), args.get(1), args.get(2)) {
+        (Some(func), Some(init), Some(Value::Int(n))) => (func, init, *n),
+        _ => {
+            return Err(LCoreError::ArgumentError(format!(
+                "ArgumentError: \"iterate\" takes a function, an initial value and an Int"
+            )))
+        }
+    };
+
+    lcore_check_callable(func)?;
+
+    if n < 0 {
+        return Err(LCoreError::ArgumentError(format!(
+            "ArgumentError: Count must not be negative ({})",
+            n
+        )));
+    }
+
+    let mut result = vec![];
+    let mut value = init.clone();
+    for i in 0..n {
+        if i > 0 {
+            value = lcore_call(
+                func,
+                &mut Value::Array(vec![value]),
+                symbol_table,
+            )?;
+        }
+        result.push(value.clone());
+    }
+
+    Ok(Value::Array(result))
+}
+
+/// Group the elements of an Array into a Dict keyed by the result of calling
+/// `func` on each element. Elements keep their order within each group.
+pub fn lcore_group_by(
+    args: &mut Value,
+    symbol_table: &mut Environment,
+) -> Result<Value, LCoreError> {
+    let args = args.as_array();
+
+    let (func, v) = match (args.first(), args.get(1)) {
+        (Some(func), Some(Value::Array(v))) => (func, v),
+        _ => {
+            return Err(LCoreError::ArgumentError(format!(
+                "ArgumentError: \"group-by\" takes a function and an Array"
+            )))
+        }
+    };
+
+    lcore_check_callable(func)?;
+
+    let mut groups: HashMap<Value, Value> = HashMap::new();
+
+    for value in v {
+        let key = lcore_call(
+            func,
+            &mut Value::Array(vec![value.clone()]),
+            symbol_table,
+        )?;
+
+        lcore_check_key(&key)?;
+
+        if let Value::Array(group) = groups
+            .entry(key)
+            .or_insert_with(|| Value::Array(Vec::new()))
+        {
+            group.push(value.clone());
+        }
+    }
+
+    Ok(Value::Dict(groups))
+}
+
+/// Count how many times each distinct element appears in an Array.
+pub fn lcore_frequencies(
+    args: &mut Value,
+    symbol_table: &mut Environment,
+) -> Result<Value, LCoreError> {
+    let v = match args.as_array().first() {
+        Some(Value::Array(v)) => v,
+        _ => {
+            return Err(LCoreError::ArgumentError(format!(
+                "ArgumentError: \"frequencies\" takes an Array"
+            )))
+        }
+    };
+
+    let mut counts: HashMap<Value, Value> = HashMap::new();
+
+    for value in v {
+        lcore_check_key(value)?;
+
+        if let Value::Int(count) =
+            counts.entry(value.clone()).or_insert(Value::Int(0))
+        {
+            *count += 1;
+        }
+    }
+
+    Ok(Value::Dict(counts))
+}
+
+/// Order two comparable values. Ints and Floats can be compared with each
+/// other, Strings are compared lexicographically, Chars by code point and
+/// False sorts before True.
+fn lcore_ordering(a: &Value, b: &Value) -> Result<Ordering, LCoreError> {
+    let ordering = match (a, b) {
+        (Value::Int(a), Value::Int(b)) => Some(a.cmp(b)),
+        (Value::Float(a), Value::Float(b)) => a.partial_cmp(b),
+        (Value::Int(a), Value::Float(b)) => (*a as f64).partial_cmp(b),
+        (Value::Float(a), Value::Int(b)) => a.partial_cmp(&(*b as f64)),
+        (Value::String(a), Value::String(b)) => Some(a.cmp(b)),
+        (Value::Char(a), Value::Char(b)) => Some(a.cmp(b)),
+        (Value::Boolean(a), Value::Boolean(b)) => Some(a.cmp(b)),
+        _ => None,
+    };
+
+    match ordering {
+        Some(o) => Ok(o),
+        None => Err(LCoreError::ArgumentError(format!(
+            "ArgumentError: Cannot compare {:?} and {:?}",
+            a, b
+        ))),
+    }
+}
+
+/// Find the element with the most extreme key. On a tie, the first element
+/// wins.
+fn lcore_extreme_by(
+    args: &mut Value,
+    symbol_table: &mut Environment,
+    fn_name: &str,
+    wanted: Ordering,
+) -> Result<Value, LCoreError> {
+    let args = args.as_array();
+
+    let (func, v) = match (args.first(), args.get(1)) {
+        (Some(func), Some(Value::Array(v))) => (func, v),
+        _ => {
+            return Err(LCoreError::ArgumentError(format!(
+                "ArgumentError: \"{}\" takes a function and an Array",
+                fn_name
+            )))
+        }
+    };
+
+    lcore_check_callable(func)?;
+
+    let mut best: Option<(Value, &Value)> = None;
+
+    for value in v {
+        let key = lcore_call(
+            func,
+            &mut Value::Array(vec![value.clone()]),
+            symbol_table,
+        )?;
+
+        best = match best {
+            Some((best_key, best_value)) => {
+                if lcore_ordering(&key, &best_key)? == wanted {
+                    Some((key, value))
+                } else {
+                    Some((best_key, best_value))
+                }
+            }
+            None => Some((key, value)),
+        };
+    }
+
+    match best {
+        Some((_, value)) => Ok(value.clone()),
+        None => Err(LCoreError::ArgumentError(format!(
+            "ArgumentError: \"{}\" of an empty Array",
+            fn_name
+        ))),
+    }
+}
+
+pub fn lcore_min_by(
+    args: &mut Value,
+    symbol_table: &mut Environment,
+) -> Result<Value, LCoreError> {
+    lcore_extreme_by(args, symbol_table, "min-by", Ordering::Less)
+}
+
+pub fn lcore_max_by(
+    args: &mut Value,
+    symbol_table: &mut Environment,
+) -> Result<Value, LCoreError> {
+    lcore_extreme_by(args, symbol_table, "max-by", Ordering::Greater)
+}
+
+/// Three-way comparison: -1 if a < b, 0 if they are equal and 1 if a > b.
+/// Ints and Floats may be mixed; False sorts before True.
+pub fn lcore_compare(
+    args: &mut Value,
+    symbol_table: &mut Environment,
+) -> Result<Value, LCoreError> {
+    match args.as_array().as_slice() {
+        [a, b] => Ok(Value::Int(match lcore_ordering(a, b)? {
+            Ordering::Less => -1,
+            Ordering::Equal => 0,
+            Ordering::Greater => 1,
+        })),
+        _ => Err(LCoreError::ArgumentError(format!(
+            "ArgumentError: \"compare\" takes exactly 2 arguments"
+        ))),
+    }
+}
+
+/// Sort an Array of comparable values and drop duplicates.
+pub fn lcore_sort_unique(
+    args: &mut Value,
+    symbol_table: &mut Environment,
+) -> Result<Value, LCoreError> {
+    let mut sorted = match args.as_array().first() {
+        Some(Value::Array(v)) => v.clone(),
+        _ => {
+            return Err(LCoreError::ArgumentError(format!(
+                "ArgumentError: \"sort-unique\" takes an Array"
+            )))
+        }
+    };
+
+    // `sort_by` can't fail, so hold on to the first comparison error
+    let mut error = None;
+    sorted.sort_by(|a, b| {
+        lcore_ordering(a, b).unwrap_or_else(|e| {
+            error.get_or_insert(e);
+            Ordering::Equal
+        })
+    });
+    if let Some(e) = error {
+        return Err(e);
+    }
+
+    sorted
+        .dedup_by(|a, b| matches!(lcore_ordering(a, b), Ok(Ordering::Equal)));
+    Ok(Value::Array(sorted))
+}
+
+/// Merge two already sorted Arrays into one sorted Array in linear time.
+/// Duplicates are kept, with elements of the first Array coming first.
+pub fn lcore_merge_sorted(
+    args: &mut Value,
+    symbol_table: &mut Environment,
+) -> Result<Value, LCoreError> {
+    let args = args.as_array();
+
+    let (left, right) = match (args.first(), args.get(1)) {
+        (Some(Value::Array(l)), Some(Value::Array(r))) => (l, r),
+        _ => {
+            return Err(LCoreError::ArgumentError(format!(
+                "ArgumentError: \"merge-sorted\" takes two Arrays"
+            )))
+        }
+    };
+
+    let mut merged = Vec::with_capacity(left.len() + right.len());
+    let (mut i, mut j) = (0, 0);
+    while i < left.len() && j < right.len() {
+        if lcore_ordering(&left[i], &right[j])? == Ordering::Greater {
+            merged.push(right[j].clone());
+            j += 1;
+        } else {
+            merged.push(left[i].clone());
+            i += 1;
+        }
+    }
+    merged.extend_from_slice(&left[i..]);
+    merged.extend_from_slice(&right[j..]);
+
+    Ok(Value::Array(merged))
+}
+
+fn lcore_dict_and_func<'a>(
+    args: &'a Value,
+    fn_name: &str,
+) -> Result<(&'a Value, &'a HashMap<Value, Value>), LCoreError> {
+    let args = args.as_array();
+
+    match (args.first(), args.get(1)) {
+        (Some(func), Some(Value::Dict(d))) => {
+            lcore_check_callable(func)?;
+            Ok((func, d))
+        }
+        _ => Err(LCoreError::ArgumentError(format!(
+            "ArgumentError: \"{}\" takes a function and a Dict",
+            fn_name
+        ))),
+    }
+}
+
+/// Return a new Dict with `func` applied to each value.
+pub fn lcore_map_values(
+    args: &mut Value,
+    symbol_table: &mut Environment,
+) -> Result<Value, LCoreError> {
+    let (func, dict) = lcore_dict_and_func(args, "map-values")?;
+    let mut result = HashMap::with_capacity(dict.len());
+
+    for (key, value) in dict {
+        let new_value = lcore_call(
+            func,
+            &mut Value::Array(vec![value.clone()]),
+            symbol_table,
+        )?;
+        result.insert(key.clone(), new_value);
+    }
+
+    Ok(Value::Dict(result))
+}
+
+/// Return a new Dict with `func` applied to each key. It is an error for two
+/// keys to map to the same new key.
+pub fn lcore_map_keys(
+    args: &mut Value,
+    symbol_table: &mut Environment,
+) -> Result<Value, LCoreError> {
+    let (func, dict) = lcore_dict_and_func(args, "map-keys")?;
+    let mut result = HashMap::with_capacity(dict.len());
+
+    for (key, value) in dict {
+        let new_key = lcore_call(
+            func,
+            &mut Value::Array(vec![key.clone()]),
+            symbol_table,
+        )?;
+
+        lcore_check_key(&new_key)?;
+
+        if result.insert(new_key, value.clone()).is_some() {
+            return Err(LCoreError::ArgumentError(format!(
+                "ArgumentError: \"map-keys\" mapped two keys to the same key"
+            )));
+        }
+    }
+
+    Ok(Value::Dict(result))
+}
+
+/// Fold over the entries of a Dict: `func` is called as `(func acc key
+/// value)` and returns the next accumulator. Dicts are unordered, so `func`
+/// should not depend on the order it sees entries in.
+pub fn lcore_reduce_kv(
+    args: &mut Value,
+    symbol_table: &mut Environment,
+) -> Result<Value, LCoreError> {
+    let args = args.as_array();
+
+    let (func, init, dict) = match (args.first(), args.get(1), args.get(2)) {
+        (Some(func), Some(init), Some(Value::Dict(d))) => (func, init, d),
+        _ => {
+            return Err(LCoreError::ArgumentError(format!(
+                "ArgumentError: \"reduce-kv\" takes a function, an initial value and a Dict"
+            )))
+        }
+    };
+
+    lcore_check_callable(func)?;
+
+    let mut acc = init.clone();
+
+    for (key, value) in dict {
+        acc = lcore_call(
+            func,
+            &mut Value::Array(vec![acc, key.clone(), value.clone()]),
+            symbol_table,
+        )?;
+    }
+
+    Ok(acc)
+}
+
+/// Return a new Dict holding only the listed keys that are present.
+pub fn lcore_select_keys(
+    args: &mut Value,
+    symbol_table: &mut Environment,
+) -> Result<Value, LCoreError> {
+    let args = args.as_array();
+
+    let (dict, keys) = match (args.first(), args.get(1)) {
+        (Some(Value::Dict(d)), Some(Value::Array(k))) => (d, k),
+        _ => {
+            return Err(LCoreError::ArgumentError(format!(
+                "ArgumentError: \"select-keys\" takes a Dict and an Array"
+            )))
+        }
+    };
+
+    let mut result = HashMap::new();
+
+    for key in keys {
+        let key = match key {
+            Value::Quote(q) => match &**q {
+                Value::Identifier(s) => Value::String(s.clone()),
+                _ => key.clone(),
+            },
+            _ => key.clone(),
+        };
+
+        if let Some(value) = dict.get(&key) {
+            result.insert(key, value.clone());
+        }
+    }
+
+    Ok(Value::Dict(result))
+}
+
+/// Turn a Dict into an Array of `[key value]` pairs, in no particular order.
+pub fn lcore_dict_to_pairs(
+    args: &mut Value,
+    symbol_table: &mut Environment,
+) -> Result<Value, LCoreError> {
+    match args.as_array().first() {
+        Some(Value::Dict(d)) => Ok(Value::Array(
+            d.iter()
+                .map(|(k, v)| Value::Array(vec![k.clone(), v.clone()]))
+                .collect(),
+        )),
+        _ => Err(LCoreError::ArgumentError(format!(
+            "ArgumentError: \"dict->pairs\" takes a Dict"
+        ))),
+    }
+}
+
+/// Build a Dict from an Array of `[key value]` pairs. Later pairs win when a
+/// key repeats.
+pub fn lcore_pairs_to_dict(
+    args: &mut Value,
+    symbol_table: &mut Environment,
+) -> Result<Value, LCoreError> {
+    let pairs = match args.as_array().first() {
+        Some(Value::Array(v)) => v,
+        _ => {
+            return Err(LCoreError::ArgumentError(format!(
+                "ArgumentError: \"pairs->dict\" takes an Array of pairs"
+            )))
+        }
+    };
+
+    let mut result = HashMap::with_capacity(pairs.len());
+
+    for pair in pairs {
+        match pair {
+            Value::Array(kv) if kv.len() == 2 => {
+                lcore_check_key(&kv[0])?;
+                result.insert(kv[0].clone(), kv[1].clone());
+            }
+            _ => {
+                return Err(LCoreError::ArgumentError(format!(
+                    "ArgumentError: Expected a [key value] pair, got {:?}",
+                    pair
+                )))
+            }
+        }
+    }
+
+    Ok(Value::Dict(result))
+}
+
+/// Build a Dict mapping each position of an Array to its element:
+/// `(array->dict-indexed ["a" "b"])` gives `{0: "a", 1: "b"}`.
+pub fn lcore_array_to_dict_indexed(
+    args: &mut Value,
+    symbol_table: &mut Environment,
+) -> Result<Value, LCoreError> {
+    match args.as_array().first() {
+        Some(Value::Array(v)) => Ok(Value::Dict(
+            v.iter()
+                .enumerate()
+                .map(|(i, value)| (Value::Int(i as i64), value.clone()))
+                .collect(),
+        )),
+        _ => Err(LCoreError::ArgumentError(format!(
+            "ArgumentError: \"array->dict-indexed\" takes an Array"
+        ))),
+    }
+}
+
+/// Pair up keys and values from two Arrays into a Dict, stopping at the end
+/// of the shorter one: `(zip-to-dict ["a" "b"] [1 2])`.
+pub fn lcore_zip_to_dict(
+    args: &mut Value,
+    symbol_table: &mut Environment,
+) -> Result<Value, LCoreError> {
+    let args = args.as_array();
+
+    let (keys, values) = match (args.first(), args.get(1)) {
+        (Some(Value::Array(k)), Some(Value::Array(v))) => (k, v),
+        _ => {
+            return Err(LCoreError::ArgumentError(format!(
+                "ArgumentError: \"zip-to-dict\" takes two Arrays"
+            )))
+        }
+    };
+
+    let mut result = HashMap::with_capacity(keys.len().min(values.len()));
+
+    for (key, value) in keys.iter().zip(values) {
+        lcore_check_key(key)?;
+        result.insert(key.clone(), value.clone());
+    }
+
+    Ok(Value::Dict(result))
+}
+
+/// Extend a dotted path like `address.city` by one key or index.
+fn lcore_join_path(path: &str, part: &str) -> String {
+    if path.is_empty() {
+        part.to_string()
+    } else {
+        format!("{}.{}", path, part)
+    }
+}
+
+/// Check `value` against `schema`, recording a message for every mismatch
+/// under its dotted path. A String schema names a type (as printed by
+/// `describe`, or "Any"), a Dict schema lists required keys and a one-element
+/// Array schema applies to every element.
+fn lcore_conform_into(
+    value: &Value,
+    schema: &Value,
+    path: &str,
+    mismatches: &mut HashMap<Value, Value>,
+) -> Result<(), LCoreError> {
+    let mut mismatch = |msg: String| {
+        mismatches.insert(Value::String(path.to_string()), Value::String(msg));
+    };
+
+    match (schema, value) {
+        (Value::String(expected), _) => {
+            let actual = format!("{:?}", value);
+            if expected != "Any" && *expected != actual {
+                mismatch(format!("expected {}, got {}", expected, actual));
+            }
+        }
+
+        (Value::Dict(fields), Value::Dict(dict)) => {
+            for (key, field_schema) in fields {
+                let field_path = match key {
+                    Value::String(k) => lcore_join_path(path, k),
+                    _ => {
+                        return Err(LCoreError::ArgumentError(format!(
+                            "ArgumentError: Schema keys must be Strings"
+                        )))
+                    }
+                };
+
+                match dict.get(key) {
+                    Some(field) => lcore_conform_into(
+                        field,
+                        field_schema,
+                        &field_path,
+                        mismatches,
+                    )?,
+                    None => {
+                        mismatches.insert(
+                            Value::String(field_path),
+                            Value::String("missing".to_string()),
+                        );
+                    }
+                }
+            }
+        }
+
+        (Value::Array(element), Value::Array(v)) if element.len() == 1 => {
+            for (i, item) in v.iter().enumerate() {
+                let item_path = lcore_join_path(path, &i.to_string());
+                lcore_conform_into(item, &element[0], &item_path, mismatches)?;
+            }
+        }
+
+        (Value::Dict(..), _) => {
+            mismatch(format!("expected Dict, got {:?}", value));
+        }
+
+        (Value::Array(element), _) if element.len() == 1 => {
+            mismatch(format!("expected Array, got {:?}", value));
+        }
+
+        _ => {
+            return Err(LCoreError::ArgumentError(format!(
+                "ArgumentError: Invalid schema ({:?})",
+                schema
+            )))
+        }
+    }
+
+    Ok(())
+}
+
+/// Validate data against a schema (see `lcore_conform_into`). Returns True if
+/// it conforms, otherwise a Dict mapping each offending path to a message.
+pub fn lcore_conform(
+    args: &mut Value,
+    symbol_table: &mut Environment,
+) -> Result<Value, LCoreError> {
+    let args = args.as_array();
+
+    let (value, schema) = match (args.first(), args.get(1)) {
+        (Some(value), Some(schema)) => (value, schema),
+        _ => {
+            return Err(LCoreError::ArgumentError(format!(
+                "ArgumentError: \"conform\" takes a value and a schema"
+            )))
+        }
+    };
+
+    let mut mismatches = HashMap::new();
+    lcore_conform_into(value, schema, "", &mut mismatches)?;
+
+    if mismatches.is_empty() {
+        Ok(Value::Boolean(true))
+    } else {
+        Ok(Value::Dict(mismatches))
+    }
+}
+
+/// Record every place where `a` and `b` differ under its dotted path, as a
+/// Dict holding the `left` and `right` values. A side is left out when the
+/// key or index only exists in the other value.
+fn lcore_diff_into(
+    a: Option<&Value>,
+    b: Option<&Value>,
+    path: &str,
+    differences: &mut HashMap<Value, Value>,
+) {
+    match (a, b) {
+        (Some(Value::Array(x)), Some(Value::Array(y))) if x != y => {
+            for i in 0..x.len().max(y.len()) {
+                let item_path = lcore_join_path(path, &i.to_string());
+                lcore_diff_into(x.get(i), y.get(i), &item_path, differences);
+            }
+        }
+
+        (Some(Value::Dict(x)), Some(Value::Dict(y))) if x != y => {
+            let mut keys: Vec<&Value> = x.keys().collect();
+            keys.extend(y.keys().filter(|k| !x.contains_key(k)));
+
+            for key in keys {
+                let key_path =
+                    lcore_join_path(path, &lcore_value_to_string(key, false));
+                lcore_diff_into(
+                    x.get(key),
+                    y.get(key),
+                    &key_path,
+                    differences,
+                );
+            }
+        }
+
+        (Some(x), Some(y)) if x == y => (),
+
+        _ => {
+            let mut sides = HashMap::new();
+            if let Some(a) = a {
+                sides.insert(Value::String("left".to_string()), a.clone());
+            }
+            if let Some(b) = b {
+                sides.insert(Value::String("right".to_string()), b.clone());
+            }
+            differences
+                .insert(Value::String(path.to_string()), Value::Dict(sides));
+        }
+    }
+}
+
+/// Describe where two values differ (see `lcore_diff_into`), or return Null
+/// if they are equal.
+pub fn lcore_diff(
+    args: &mut Value,
+    symbol_table: &mut Environment,
+) -> Result<Value, LCoreError> {
+    let args = args.as_array();
+
+    let (a, b) = match (args.first(), args.get(1)) {
+        (Some(a), Some(b)) => (a, b),
+        _ => {
+            return Err(LCoreError::ArgumentError(format!(
+                "ArgumentError: \"diff\" takes exactly 2 arguments"
+            )))
+        }
+    };
+
+    let mut differences = HashMap::new();
+    lcore_diff_into(Some(a), Some(b), "", &mut differences);
+
+    if differences.is_empty() {
+        Ok(Value::Null)
+    } else {
+        Ok(Value::Dict(differences))
+    }
+}
+
+/// Return the hash a Dict would use for the given key. Useful for finding out
+/// why two keys do or don't collide.
+pub fn lcore_hash_of(
+    args: &mut Value,
+    symbol_table: &mut Environment,
+) -> Result<Value, LCoreError> {
+    let [value] = lcore_take_args(args, "hash-of")?;
+
+    if value.is_hashable() {
+        let mut hasher = DefaultHasher::new();
+        value.hash(&mut hasher);
+        Ok(Value::Int(hasher.finish() as i64))
+    } else {
+        Err(LCoreError::ArgumentError(format!(
+            "ArgumentError: {:?} is not hashable",
+            value
+        )))
+    }
+}
+
+/// Return an independent deep copy of a value. Nested Arrays, Dicts and
+/// Structs are copied too, so mutating the copy (e.g. with `swap`) never
+/// affects the original.
+pub fn lcore_copy(
+    args: &mut Value,
+    symbol_table: &mut Environment,
+) -> Result<Value, LCoreError> {
+    let [value] = lcore_take_args(args, "copy")?;
+    Ok(value.clone())
+}
+
+/// Count the scalar leaves of a value and measure how deeply its Arrays and
+/// Dicts nest. Dict keys are not counted. Uses an explicit stack rather than
+/// recursion so very deep structures can't overflow the native stack.
+fn lcore_measure(value: &Value) -> (usize, usize) {
+    let mut leaves = 0;
+    let mut deepest = 0;
+    let mut stack = vec![(value, 0)];
+
+    while let Some((value, depth)) = stack.pop() {
+        match value {
+            Value::Array(v) => {
+                deepest = deepest.max(depth + 1);
+                stack.extend(v.iter().map(|child| (child, depth + 1)));
+            }
+            Value::Dict(d) => {
+                deepest = deepest.max(depth + 1);
+                stack.extend(d.values().map(|child| (child, depth + 1)));
+            }
+            _ => {
+                leaves += 1;
+                deepest = deepest.max(depth);
+            }
+        }
+    }
+
+    (leaves, deepest)
+}
+
+/// The number of scalar values inside a (possibly nested) Array or Dict. A
+/// scalar on its own has size 1.
+pub fn lcore_deep_size(
+    args: &mut Value,
+    symbol_table: &mut Environment,
+) -> Result<Value, LCoreError> {
+    let [value] = lcore_take_args(args, "deep-size")?;
+    Ok(Value::Int(lcore_measure(value).0 as i64))
+}
+
+/// How many Arrays and Dicts deep a value goes. Scalars have depth 0.
+pub fn lcore_depth(
+    args: &mut Value,
+    symbol_table: &mut Environment,
+) -> Result<Value, LCoreError> {
+    let [value] = lcore_take_args(args, "depth")?;
+    Ok(Value::Int(lcore_measure(value).1 as i64))
+}
+
+pub fn lcore_len(
+    args: &mut Value,
+    symbol_table: &mut Environment,
+) -> Result<Value, LCoreError> {
+    let [arg] = lcore_take_args(args, "len")?;
+
+    return match arg {
+        Value::Array(v) => Ok(Value::Int(v.len() as i64)),
+        Value::Dict(v) => Ok(Value::Int(v.len() as i64)),
+        Value::String(v) => Ok(Value::Int(v.len() as i64)),
+        Value::Bytes(v) => Ok(Value::Int(v.len() as i64)),
+        Value::Quote(v) => Ok(Value::Int(1)),
+        _ => Err(LCoreError::ArgumentError(format!(
+            "ArgumentError: {:?} has no length",
+            arg
+        ))),
+    };
+}
+
+fn lcore_is_empty(
+    args: &mut Value,
+    fn_name: &str,
+) -> Result<bool, LCoreError> {
+    let [arg] = lcore_take_args(args, fn_name)?;
+
+    match arg {
+        Value::Array(v) => Ok(v.is_empty()),
+        Value::Dict(v) => Ok(v.is_empty()),
+        Value::String(v) => Ok(v.is_empty()),
+        _ => Err(LCoreError::ArgumentError(format!(
+            "ArgumentError: {:?} is not a collection",
+            arg
+        ))),
+    }
+}
+
+pub fn lcore_empty(
+    args: &mut Value,
+    symbol_table: &mut Environment,
+) -> Result<Value, LCoreError> {
+    Ok(Value::Boolean(lcore_is_empty(args, "empty?")?))
+}
+
+pub fn lcore_not_empty(
+    args: &mut Value,
+    symbol_table: &mut Environment,
+) -> Result<Value, LCoreError> {
+    Ok(Value::Boolean(!lcore_is_empty(args, "not-empty?")?))
+}
+
+pub fn lcore_equals(
+    args: &mut Value,
+    symbol_table: &mut Environment,
+) -> Result<Value, LCoreError> {
+    let [a, b] = lcore_take_args(args, "=")?;
+
+    match (a, b) {
+        (Value::Null, Value::Null) => Ok(Value::Boolean(true)),
+        (Value::Int(a), Value::Int(b)) => Ok(Value::Boolean(a == b)),
+        (Value::Float(a), Value::Float(b)) => Ok(Value::Boolean(a == b)),
+        (Value::String(a), Value::String(b)) => Ok(Value::Boolean(a == b)),
+        (Value::Char(a), Value::Char(b)) => Ok(Value::Boolean(a == b)),
+        (Value::Boolean(a), Value::Boolean(b)) => Ok(Value::Boolean(a == b)),
+        (Value::Identifier(a), Value::Identifier(b)) => {
+            Ok(Value::Boolean(a == b))
+        }
+        (Value::Dict(a), Value::Dict(b)) => Ok(Value::Boolean(a == b)),
+        (Value::Array(a), Value::Array(b)) => Ok(Value::Boolean(a == b)),
+        (Value::Bytes(a), Value::Bytes(b)) => Ok(Value::Boolean(a == b)),
+        (Value::Struct { .. }, Value::Struct { .. }) => {
+            Ok(Value::Boolean(a == b))
+        }
+        (Value::Quote(a), Value::Quote(b)) => lcore_equals(
+            &mut Value::Array(vec![*a.clone(), *b.clone()]),
+            symbol_table,
+        ),
+
+        (Value::Func { f: a }, Value::Func { f: b }) => {
+            Ok(Value::Boolean(*a as usize == *b as usize))
+        }
+
+        _ => Err(LCoreError::ArgumentError(format!(
+            "ArgumentError: Type mismatch ({:?} and {:?})",
+            a, b
+        ))),
+    }
+}
+
+pub fn lcore_not_equals(
+    args: &mut Value,
+    symbol_table: &mut Environment,
+) -> Result<Value, LCoreError> {
+    let [a, b] = lcore_take_args(args, "!=")?;
+
+    match (a, b) {
+        (Value::Null, Value::Null) => Ok(Value::Boolean(false)),
+        (Value::Int(a), Value::Int(b)) => Ok(Value::Boolean(a != b)),
+        (Value::Float(a), Value::Float(b)) => Ok(Value::Boolean(a != b)),
+        (Value::String(a), Value::String(b)) => Ok(Value::Boolean(a != b)),
+        (Value::Char(a), Value::Char(b)) => Ok(Value::Boolean(a != b)),
+        (Value::Boolean(a), Value::Boolean(b)) => Ok(Value::Boolean(a != b)),
+        (Value::Identifier(a), Value::Identifier(b)) => {
+            Ok(Value::Boolean(a != b))
+        }
+        (Value::Dict(a), Value::Dict(b)) => Ok(Value::Boolean(a != b)),
+        (Value::Array(a), Value::Array(b)) => Ok(Value::Boolean(a != b)),
+        (Value::Bytes(a), Value::Bytes(b)) => Ok(Value::Boolean(a != b)),
+        (Value::Struct { .. }, Value::Struct { .. }) => {
+            Ok(Value::Boolean(a != b))
+        }
+        (Value::Quote(a), Value::Quote(b)) => lcore_not_equals(
+            &mut Value::Array(vec![*a.clone(), *b.clone()]),
+            symbol_table,
+        ),
+
+        (Value::Func { f: a }, Value::Func { f: b }) => {
+            Ok(Value::Boolean(*a as usize != *b as usize))
+        }
+
+        _ => Err(LCoreError::ArgumentError(format!(
+            "ArgumentError: Type mismatch ({:?} and {:?})",
+            a, b
+        ))),
+    }
+}
+
+/// Compare two Strings for equality, ignoring case.
+pub fn lcore_equals_ignore_case(
+    args: &mut Value,
+    symbol_table: &mut Environment,
+) -> Result<Value, LCoreError> {
+    let [a, b] = lcore_take_args(args, "=i")?;
+
+    match (a, b) {
+        (Value::String(a), Value::String(b)) => {
+            Ok(Value::Boolean(a.to_lowercase() == b.to_lowercase()))
+        }
+
+        _ => Err(LCoreError::ArgumentError(format!(
+            "ArgumentError: \"=i\" takes two Strings, got {:?} and {:?}",
+            a, b
+        ))),
+    }
+}
+
+/// Raise an error showing both values if `actual` and `expected` differ.
+/// Values of different types are simply unequal rather than an error.
+pub fn lcore_assert_eq(
+    args: &mut Value,
+    symbol_table: &mut Environment,
+) -> Result<Value, LCoreError> {
+    let (actual, expected) = match args.as_array().as_slice() {
+        [actual, expected] => (actual, expected),
+        _ => {
+            return Err(LCoreError::ArgumentError(format!(
+                "ArgumentError: \"assert-eq\" takes exactly 2 arguments"
+            )))
+        }
+    };
+
+    if actual == expected {
+        Ok(Value::Null)
+    } else {
+        Err(LCoreError::LambdaCoreError(format!(
+            "LambdaCoreError: Assertion failed: expected {} but got {}",
+            lcore_value_to_string(expected, true),
+            lcore_value_to_string(actual, true)
+        )))
+    }
+}
+
+pub fn lcore_less_than(
+    args: &mut Value,
+    symbol_table: &mut Environment,
+) -> Result<Value, LCoreError> {
+    let [a, b] = lcore_take_args(args, "<")?;
+
+    match (a, b) {
+        (Value::Int(a), Value::Int(b)) => Ok(Value::Boolean(a < b)),
+        (Value::Float(a), Value::Float(b)) => Ok(Value::Boolean(a < b)),
+        (Value::String(a), Value::String(b)) => {
+            Ok(Value::Boolean(a.len() < b.len()))
+        }
+        (Value::Identifier(a), Value::Identifier(b)) => {
+            Ok(Value::Boolean(a.len() < b.len()))
+        }
+        (Value::Dict(a), Value::Dict(b)) => {
+            Ok(Value::Boolean(a.len() < b.len()))
+        }
+        (Value::Array(a), Value::Array(b)) => {
+            Ok(Value::Boolean(a.len() < b.len()))
+        }
+        (Value::Quote(a), Value::Quote(b)) => lcore_less_than(
+            &mut Value::Array(vec![*a.clone(), *b.clone()]),
+            symbol_table,
+        ),
+
+        _ => Err(LCoreError::ArgumentError(format!(
+            "ArgumentError: Invalid Argument Types ({:?} and {:?})",
+            a, b
+        ))),
+    }
+}
+
+pub fn lcore_logical_or(
+    args: &mut Value,
+    symbol_table: &mut Environment,
+) -> Result<Value, LCoreError> {
+    let [a, b] = lcore_take_args(args, "or")?;
+
+    match (a, b) {
+        (Value::Boolean(a), Value::Boolean(b)) => Ok(Value::Boolean(a | b)),
+
+        _ => Err(LCoreError::ArgumentError(format!(
+            "ArgumentError: Not booleans ({:?} and {:?})",
+            a, b
+        ))),
+    }
+}
+
+pub fn lcore_logical_and(
+    args: &mut Value,
+    symbol_table: &mut Environment,
+) -> Result<Value, LCoreError> {
+    let [a, b] = lcore_take_args(args, "and")?;
+
+    match (a, b) {
+        (Value::Boolean(a), Value::Boolean(b)) => Ok(Value::Boolean(a & b)),
+
+        _ => Err(LCoreError::ArgumentError(format!(
+            "ArgumentError: Not booleans ({:?} and {:?})",
+            a, b
+        ))),
+    }
+}
+
+/// Run quoted Boolean operands in order until one of them is `stop_on`.
+fn lcore_short_circuit(
+    args: &Value,
+    symbol_table: &mut Environment,
+    fn_name: &str,
+    stop_on: bool,
+) -> Result<Value, LCoreError> {
+    for operand in args.as_array() {
+        match lcore_run_body(operand, fn_name, symbol_table)? {
+            Value::Boolean(b) if b == stop_on => {
+                return Ok(Value::Boolean(stop_on))
+            }
+            Value::Boolean(..) => (),
+            other => {
+                return Err(LCoreError::ArgumentError(format!(
+                    "ArgumentError: \"{}\" operands must be Booleans, \
+                     got {:?}",
+                    fn_name, other
+                )))
+            }
+        }
+    }
+
+    Ok(Value::Boolean(!stop_on))
+}
+
+/// Short-circuiting `or`: `(or* '[a] '[b])` only runs `b` if `a` is False.
+pub fn lcore_short_or(
+    args: &mut Value,
+    symbol_table: &mut Environment,
+) -> Result<Value, LCoreError> {
+    lcore_short_circuit(args, symbol_table, "or*", true)
+}
+
+/// Short-circuiting `and`: `(and* '[a] '[b])` only runs `b` if `a` is True.
+pub fn lcore_short_and(
+    args: &mut Value,
+    symbol_table: &mut Environment,
+) -> Result<Value, LCoreError> {
+    lcore_short_circuit(args, symbol_table, "and*", false)
+}
+
+pub fn lcore_logical_not(
+    args: &mut Value,
+    symbol_table: &mut Environment,
+) -> Result<Value, LCoreError> {
+    let [a] = lcore_take_args(args, "not")?;
+
+    if let Value::Boolean(b) = a {
+        return Ok(Value::Boolean(!b));
+    } else {
+        return Err(LCoreError::ArgumentError(format!(
+            "ArgumentError: Not a boolean ({:?})",
+            a
+        )));
+    }
+}
+
+/// Coerce any value to a Boolean using `Value::is_truthy`.
+pub fn lcore_truthy(
+    args: &mut Value,
+    symbol_table: &mut Environment,
+) -> Result<Value, LCoreError> {
+    let [value] = lcore_take_args(args, "truthy?")?;
+    Ok(Value::Boolean(value.is_truthy()))
+}
+
+/// True for Ints and Floats, False for anything else.
+pub fn lcore_is_number(
+    args: &mut Value,
+    symbol_table: &mut Environment,
+) -> Result<Value, LCoreError> {
+    Ok(Value::Boolean(matches!(
+        args.as_array().first(),
+        Some(Value::Int(..)) | Some(Value::Float(..))
+    )))
+}
+
+/// Check whether a String holds a decimal Int or Float, such as `"-12"`,
+/// `"3.5"` or `"1e3"`, without converting it. Words like `"inf"` and
+/// `"NaN"` that Rust would accept don't count.
+pub fn lcore_is_numeric_string(
+    args: &mut Value,
+    symbol_table: &mut Environment,
+) -> Result<Value, LCoreError> {
+    match args.as_array().first() {
+        Some(Value::String(s)) => {
+            let digits =
+                s.chars().all(|c| c.is_ascii_digit() || "+-.eE".contains(c));
+            Ok(Value::Boolean(
+                s.parse::<i64>().is_ok()
+                    || (digits && s.parse::<f64>().is_ok()),
+            ))
+        }
+        _ => Err(LCoreError::ArgumentError(format!(
+            "ArgumentError: \"numeric-string?\" takes a String"
+        ))),
+    }
+}
+
+fn lcore_io_error(path: &str, err: io::Error) -> LCoreError {
+    LCoreError::LambdaCoreError(format!("LambdaCoreError: {}: {}", path, err))
+}
+
+fn lcore_path_arg<'a>(
+    args: &'a Value,
+    fn_name: &str,
+) -> Result<&'a String, LCoreError> {
+    match args.as_array().first() {
+        Some(Value::String(path)) => Ok(path),
+        _ => Err(LCoreError::ArgumentError(format!(
+            "ArgumentError: \"{}\" takes a path String",
+            fn_name
+        ))),
+    }
+}
+
+/// List the names of the entries in a directory, sorted.
+pub fn lcore_read_dir(
+    args: &mut Value,
+    symbol_table: &mut Environment,
+) -> Result<Value, LCoreError> {
+    let path = lcore_path_arg(args, "read-dir")?;
+    let entries = fs::read_dir(path).map_err(|e| lcore_io_error(path, e))?;
+
+    let mut names = vec![];
+    for entry in entries {
+        let entry = entry.map_err(|e| lcore_io_error(path, e))?;
+        names.push(entry.file_name().to_string_lossy().to_string());
+    }
+    names.sort();
+
+    Ok(Value::Array(names.into_iter().map(Value::String).collect()))
+}
+
+/// Resolve a path to its canonical absolute form.
+pub fn lcore_abs_path(
+    args: &mut Value,
+    symbol_table: &mut Environment,
+) -> Result<Value, LCoreError> {
+    let path = lcore_path_arg(args, "abs-path")?;
+
+    match fs::canonicalize(path) {
+        Ok(abs) => Ok(Value::String(abs.to_string_lossy().to_string())),
+        Err(e) => Err(lcore_io_error(path, e)),
+    }
+}
+
+/// True if anything exists at the given path. Never errors on a missing path.
+pub fn lcore_exists(
+    args: &mut Value,
+    symbol_table: &mut Environment,
+) -> Result<Value, LCoreError> {
+    let path = lcore_path_arg(args, "exists?")?;
+    Ok(Value::Boolean(Path::new(path).exists()))
+}
+
+/// True if the path is a regular file (following symlinks).
+pub fn lcore_is_file(
+    args: &mut Value,
+    symbol_table: &mut Environment,
+) -> Result<Value, LCoreError> {
+    let path = lcore_path_arg(args, "is-file?")?;
+    Ok(Value::Boolean(Path::new(path).is_file()))
+}
+
+/// True if the path is a directory (following symlinks).
+pub fn lcore_is_dir(
+    args: &mut Value,
+    symbol_table: &mut Environment,
+) -> Result<Value, LCoreError> {
+    let path = lcore_path_arg(args, "is-dir?")?;
+    Ok(Value::Boolean(Path::new(path).is_dir()))
+}
+
+/// Append a String to the end of a file, creating the file if needed.
+pub fn lcore_append_file(
+    args: &mut Value,
+    symbol_table: &mut Environment,
+) -> Result<Value, LCoreError> {
+    let args = args.as_array();
+
+    let (path, contents) = match (args.first(), args.get(1)) {
+        (Some(Value::String(p)), Some(Value::String(c))) => (p, c),
+        _ => {
+            return Err(LCoreError::ArgumentError(format!(
+                "ArgumentError: \"append-file\" takes a path String and a content String"
+            )))
+        }
+    };
+
+    OpenOptions::new()
+        .append(true)
+        .create(true)
+        .open(path)
+        .and_then(|mut file| file.write_all(contents.as_bytes()))
+        .map_err(|e| lcore_io_error(path, e))?;
+
+    Ok(Value::Null)
+}
+
+/// Run a program with an optional Array of String arguments and wait for it,
+/// returning a Dict of its `stdout`, `stderr` and exit `code`. This runs any
+/// process the script asks for, with the interpreter's own permissions.
+pub fn lcore_system(
+    args: &mut Value,
+    symbol_table: &mut Environment,
+) -> Result<Value, LCoreError> {
+    let args = args.as_array();
+
+    let (program, program_args) = match (args.first(), args.get(1)) {
+        (Some(Value::String(p)), None) => (p, vec![]),
+        (Some(Value::String(p)), Some(Value::Array(a))) => (p, a.clone()),
+        _ => {
+            return Err(LCoreError::ArgumentError(format!(
+                "ArgumentError: \"system\" takes a program String and an Array of arguments"
+            )))
+        }
+    };
+
+    let mut command = Command::new(program);
+    for arg in &program_args {
+        match arg {
+            Value::String(s) => command.arg(s),
+            _ => {
+                return Err(LCoreError::ArgumentError(format!(
+                    "ArgumentError: \"system\" arguments must be Strings, got {:?}",
+                    arg
+                )))
+            }
+        };
+    }
+
+    let output = command.output().map_err(|e| lcore_io_error(program, e))?;
+
+    let mut result = HashMap::new();
+    result.insert(
+        Value::String("stdout".to_string()),
+        Value::String(String::from_utf8_lossy(&output.stdout).to_string()),
+    );
+    result.insert(
+        Value::String("stderr".to_string()),
+        Value::String(String::from_utf8_lossy(&output.stderr).to_string()),
+    );
+    // A process killed by a signal has no exit code
+    result.insert(
+        Value::String("code".to_string()),
+        match output.status.code() {
+            Some(code) => Value::Int(code as i64),
+            None => Value::Null,
+        },
+    );
+
+    Ok(Value::Dict(result))
+}
+
+fn lcore_lines(text: &str) -> Value {
+    Value::Array(text.lines().map(|l| Value::String(l.to_string())).collect())
+}
+
+/// Split a String on `\n` or `\r\n`. A trailing newline does not produce a
+/// trailing empty line.
+pub fn lcore_split_lines(
+    args: &mut Value,
+    symbol_table: &mut Environment,
+) -> Result<Value, LCoreError> {
+    match args.as_array().first() {
+        Some(Value::String(text)) => Ok(lcore_lines(text)),
+        _ => Err(LCoreError::ArgumentError(format!(
+            "ArgumentError: \"split-lines\" takes a String"
+        ))),
+    }
+}
+
+/// Read a file and split it into lines the same way as `split-lines`.
+pub fn lcore_read_lines(
+    args: &mut Value,
+    symbol_table: &mut Environment,
+) -> Result<Value, LCoreError> {
+    let path = lcore_path_arg(args, "read-lines")?;
+
+    match fs::read_to_string(path) {
+        Ok(text) => Ok(lcore_lines(&text)),
+        Err(e) => Err(lcore_io_error(path, e)),
+    }
+}
+
+/// Read a whole file as Bytes, without decoding it.
+pub fn lcore_read_bytes(
+    args: &mut Value,
+    symbol_table: &mut Environment,
+) -> Result<Value, LCoreError> {
+    let path = lcore_path_arg(args, "read-bytes")?;
+
+    match fs::read(path) {
+        Ok(bytes) => Ok(Value::Bytes(bytes)),
+        Err(e) => Err(lcore_io_error(path, e)),
+    }
+}
+
+/// Decode Bytes as UTF-8. Invalid UTF-8 is an ArgumentError.
+pub fn lcore_bytes_to_string(
+    args: &mut Value,
+    symbol_table: &mut Environment,
+) -> Result<Value, LCoreError> {
+    match args.as_array().first() {
+        Some(Value::Bytes(bytes)) => match String::from_utf8(bytes.clone()) {
+            Ok(s) => Ok(Value::String(s)),
+            Err(e) => Err(LCoreError::ArgumentError(format!(
+                "ArgumentError: Invalid UTF-8 in Bytes: {}",
+                e
+            ))),
+        },
+        _ => Err(LCoreError::ArgumentError(format!(
+            "ArgumentError: \"bytes->string\" takes Bytes"
+        ))),
+    }
+}
+
+/// Encode the UTF-8 bytes of a String as lowercase hex digits.
+pub fn lcore_hex_encode(
+    args: &mut Value,
+    symbol_table: &mut Environment,
+) -> Result<Value, LCoreError> {
+    match args.as_array().first() {
+        Some(Value::String(s)) => Ok(Value::String(
+            s.bytes().map(|b| format!("{:02x}", b)).collect(),
+        )),
+        _ => Err(LCoreError::ArgumentError(format!(
+            "ArgumentError: \"hex-encode\" takes a String"
+        ))),
+    }
+}
+
+/// Decode a String of hex digit pairs back into the String they encode.
+pub fn lcore_hex_decode(
+    args: &mut Value,
+    symbol_table: &mut Environment,
+) -> Result<Value, LCoreError> {
+    let hex = match args.as_array().first() {
+        Some(Value::String(s)) => s,
+        _ => {
+            return Err(LCoreError::ArgumentError(format!(
+                "ArgumentError: \"hex-decode\" takes a String"
+            )))
+        }
+    };
+
+    if hex.len() % 2 != 0 {
+        return Err(LCoreError::ArgumentError(format!(
+            "ArgumentError: Odd number of hex digits ({})",
+            hex.len()
+        )));
+    }
+
+    let mut bytes = Vec::with_capacity(hex.len() / 2);
+    for pair in hex.as_bytes().chunks(2) {
+        let digits = String::from_utf8_lossy(pair);
+        match u8::from_str_radix(&digits, 16) {
+            Ok(byte) if pair.iter().all(u8::is_ascii_hexdigit) => {
+                bytes.push(byte)
+            }
+            _ => {
+                return Err(LCoreError::ArgumentError(format!(
+                    "ArgumentError: Invalid hex digits \"{}\"",
+                    digits
+                )))
+            }
+        }
+    }
+
+    String::from_utf8(bytes).map(Value::String).map_err(|e| {
+        LCoreError::ArgumentError(format!(
+            "ArgumentError: Hex does not decode to UTF-8: {}",
+            e
+        ))
+    })
+}
+
+const BASE64_ALPHABET: &[u8; 64] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+/// Encode the UTF-8 bytes of a String as padded standard base64.
+pub fn lcore_base64_encode(
+    args: &mut Value,
+    symbol_table: &mut Environment,
+) -> Result<Value, LCoreError> {
+    let bytes = match args.as_array().first() {
+        Some(Value::String(s)) => s.as_bytes(),
+        _ => {
+            return Err(LCoreError::ArgumentError(format!(
+                "ArgumentError: \"base64-encode\" takes a String"
+            )))
+        }
+    };
+
+    let mut encoded = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let group = chunk
+            .iter()
+            .enumerate()
+            .fold(0u32, |acc, (i, b)| acc | (*b as u32) << (16 - 8 * i));
+
+        for i in 0..4 {
+            if i <= chunk.len() {
+                let index = (group >> (18 - 6 * i)) & 0x3f;
+                encoded.push(BASE64_ALPHABET[index as usize] as char);
+            } else {
+                encoded.push('=');
+            }
+        }
+    }
+
+    Ok(Value::String(encoded))
+}
+
+/// Decode padded standard base64 back into the String it encodes.
+pub fn lcore_base64_decode(
+    args: &mut Value,
+    symbol_table: &mut Environment,
+) -> Result<Value, LCoreError> {
+    let text = match args.as_array().first() {
+        Some(Value::String(s)) => s,
+        _ => {
+            return Err(LCoreError::ArgumentError(format!(
+                "ArgumentError: \"base64-decode\" takes a String"
+            )))
+        }
+    };
+
+    let invalid = || {
+        LCoreError::ArgumentError(format!(
+            "ArgumentError: Invalid base64 \"{}\"",
+            text
+        ))
+    };
+
+    if text.len() % 4 != 0 {
+        return Err(invalid());
+    }
+
+    let mut bytes = Vec::with_capacity(text.len() / 4 * 3);
+    let chunks: Vec<&[u8]> = text.as_bytes().chunks(4).collect();
+    for (n, chunk) in chunks.iter().enumerate() {
+        // Padding may only appear at the end of the last group
+        let padding = chunk.iter().rev().take_while(|b| **b == b'=').count();
+        if padding > 2 || (padding > 0 && n + 1 != chunks.len()) {
+            return Err(invalid());
+        }
+
+        let mut group = 0u32;
+        for (i, b) in chunk[..4 - padding].iter().enumerate() {
+            let index = match BASE64_ALPHABET.iter().position(|c| c == b) {
+                Some(index) => index as u32,
+                None => return Err(invalid()),
+            };
+            group |= index << (18 - 6 * i);
+        }
+
+        for i in 0..3 - padding {
+            bytes.push((group >> (16 - 8 * i)) as u8);
+        }
+    }
+
+    String::from_utf8(bytes).map(Value::String).map_err(|e| {
+        LCoreError::ArgumentError(format!(
+            "ArgumentError: Base64 does not decode to UTF-8: {}",
+            e
+        ))
+    })
+}
+
+/// Report the lines of code in a file (see `lcore_loc_of`).
+pub fn lcore_loc(
+    args: &mut Value,
+    symbol_table: &mut Environment,
+) -> Result<Value, LCoreError> {
+    let path = lcore_path_arg(args, "loc")?;
+    let source =
+        fs::read_to_string(path).map_err(|e| lcore_io_error(path, e))?;
+
+    Ok(Value::Int(lcore_loc_of(&source)? as i64))
+}
+
+/// True if a String of source code parses. The code is never run.
+pub fn lcore_parse_ok(
+    args: &mut Value,
+    symbol_table: &mut Environment,
+) -> Result<Value, LCoreError> {
+    match args.as_array().first() {
+        Some(Value::String(source)) => {
+            Ok(Value::Boolean(lcore_parse_error(source).is_none()))
+        }
+        _ => Err(LCoreError::ArgumentError(format!(
+            "ArgumentError: \"parse-ok?\" takes a String"
+        ))),
+    }
+}
+
+/// The parser's message for a String of source code that doesn't parse, or
+/// Null if it does. The code is never run.
+pub fn lcore_parse_error_of(
+    args: &mut Value,
+    symbol_table: &mut Environment,
+) -> Result<Value, LCoreError> {
+    match args.as_array().first() {
+        Some(Value::String(source)) => match lcore_parse_error(source) {
+            Some(msg) => Ok(Value::String(msg)),
+            None => Ok(Value::Null),
+        },
+        _ => Err(LCoreError::ArgumentError(format!(
+            "ArgumentError: \"parse-error\" takes a String"
+        ))),
+    }
+}
+
+/// The display form of a value, as `print` would show it.
+pub fn lcore_to_str(
+    args: &mut Value,
+    symbol_table: &mut Environment,
+) -> Result<Value, LCoreError> {
+    let [value] = lcore_take_args(args, "to-str")?;
+    Ok(Value::String(lcore_value_to_string(value, false)))
+}
+
+/// The re-readable form of a value, with Strings quoted: `(repr "hi")` gives
+/// `"\"hi\""` where `to-str` gives `"hi"`.
+pub fn lcore_repr(
+    args: &mut Value,
+    symbol_table: &mut Environment,
+) -> Result<Value, LCoreError> {
+    let [value] = lcore_take_args(args, "repr")?;
+    Ok(Value::String(lcore_value_to_string(value, true)))
+}
+
+/// Read a value back from the text `repr` gives for it, without evaluating
+/// anything.
+pub fn lcore_read(
+    args: &mut Value,
+    symbol_table: &mut Environment,
+) -> Result<Value, LCoreError> {
+    match args.as_array().first() {
+        Some(Value::String(source)) => lcore_read_value(source),
+        _ => Err(LCoreError::ArgumentError(format!(
+            "ArgumentError: \"read\" takes a String"
+        ))),
+    }
+}
+
+/// Split a String into an Array of one-character Strings.
+pub fn lcore_string_to_chars(
+    args: &mut Value,
+    symbol_table: &mut Environment,
+) -> Result<Value, LCoreError> {
+    match args.as_array().first() {
+        Some(Value::String(s)) => Ok(Value::Array(
+            s.chars().map(|c| Value::String(c.to_string())).collect(),
+        )),
+        _ => Err(LCoreError::ArgumentError(format!(
+            "ArgumentError: \"string->chars\" takes a String"
+        ))),
+    }
+}
+
+/// Join an Array of one-character Strings back into a String.
+pub fn lcore_chars_to_string(
+    args: &mut Value,
+    symbol_table: &mut Environment,
+) -> Result<Value, LCoreError> {
+    let chars = match args.as_array().first() {
+        Some(Value::Array(a)) => a,
+        _ => {
+            return Err(LCoreError::ArgumentError(format!(
+                "ArgumentError: \"chars->string\" takes an Array"
+            )))
+        }
+    };
+
+    let mut result = String::new();
+
+    for c in chars {
+        match c {
+            Value::String(s) if s.chars().count() == 1 => result.push_str(s),
+            _ => {
+                return Err(LCoreError::ArgumentError(format!(
+                    "ArgumentError: Expected a one-character String, got {:?}",
+                    c
+                )))
+            }
+        }
+    }
+
+    Ok(Value::String(result))
+}
+
+/// Convert a Unicode code point into a Char.
+pub fn lcore_chr(
+    args: &mut Value,
+    symbol_table: &mut Environment,
+) -> Result<Value, LCoreError> {
+    match args.as_array().first() {
+        Some(Value::Int(i)) => {
+            match u32::try_from(*i).ok().and_then(char::from_u32) {
+                Some(c) => Ok(Value::Char(c)),
+                None => Err(LCoreError::ArgumentError(format!(
+                    "ArgumentError: {} is not a valid character code",
+                    i
+                ))),
+            }
+        }
+        _ => Err(LCoreError::ArgumentError(format!(
+            "ArgumentError: \"chr\" takes an Int"
+        ))),
+    }
+}
+
+/// A Char, or a String holding exactly one character.
+fn lcore_as_char(value: &Value) -> Option<char> {
+    match value {
+        Value::Char(c) => Some(*c),
+        Value::String(s) if s.chars().count() == 1 => s.chars().next(),
+        _ => None,
+    }
+}
+
+/// Convert a Char (or one-character String) into its Unicode code point.
+pub fn lcore_ord(
+    args: &mut Value,
+    symbol_table: &mut Environment,
+) -> Result<Value, LCoreError> {
+    match args.as_array().first().and_then(lcore_as_char) {
+        Some(c) => Ok(Value::Int(c as i64)),
+        None => Err(LCoreError::ArgumentError(format!(
+            "ArgumentError: \"ord\" takes a Char"
+        ))),
+    }
+}
+
+/// Count how many times a single character occurs in a String.
+pub fn lcore_char_count(
+    args: &mut Value,
+    symbol_table: &mut Environment,
+) -> Result<Value, LCoreError> {
+    let args = args.as_array();
+
+    match (args.first(), args.get(1).and_then(lcore_as_char)) {
+        (Some(Value::String(s)), Some(c)) => {
+            Ok(Value::Int(s.chars().filter(|&x| x == c).count() as i64))
+        }
+        _ => Err(LCoreError::ArgumentError(format!(
+            "ArgumentError: \"char-count\" takes a String and a single character"
+        ))),
+    }
+}
+
+/// Replace every occurrence of one character in a String with another:
+/// `(string-replace-char "a-b-c" "-" "_")`.
+pub fn lcore_string_replace_char(
+    args: &mut Value,
+    symbol_table: &mut Environment,
+) -> Result<Value, LCoreError> {
+    let args = args.as_array();
+
+    match (
+        args.first(),
+        args.get(1).and_then(lcore_as_char),
+        args.get(2).and_then(lcore_as_char),
+    ) {
+        (Some(Value::String(s)), Some(from), Some(to)) => Ok(Value::String(
+            s.chars().map(|c| if c == from { to } else { c }).collect(),
+        )),
+        _ => Err(LCoreError::ArgumentError(format!(
+            "ArgumentError: \"string-replace-char\" takes a String and two single characters"
+        ))),
+    }
+}
+
+/// Apply a character test to a Char, or to every character of a String. The
+/// empty String never matches.
+fn lcore_char_class(
+    args: &Value,
+    fn_name: &str,
+    test: fn(&char) -> bool,
+) -> Result<Value, LCoreError> {
+    match args.as_array().first() {
+        Some(Value::Char(c)) => Ok(Value::Boolean(test(c))),
+        Some(Value::String(s)) => {
+            Ok(Value::Boolean(!s.is_empty() && s.chars().all(|c| test(&c))))
+        }
+        _ => Err(LCoreError::ArgumentError(format!(
+            "ArgumentError: \"{}\" takes a String or a Char",
+            fn_name
+        ))),
+    }
+}
+
+pub fn lcore_is_digit(
+    args: &mut Value,
+    symbol_table: &mut Environment,
+) -> Result<Value, LCoreError> {
+    lcore_char_class(args, "digit?", char::is_ascii_digit)
+}
+
+pub fn lcore_is_alpha(
+    args: &mut Value,
+    symbol_table: &mut Environment,
+) -> Result<Value, LCoreError> {
+    lcore_char_class(args, "alpha?", |c| c.is_alphabetic())
+}
+
+pub fn lcore_is_whitespace(
+    args: &mut Value,
+    symbol_table: &mut Environment,
+) -> Result<Value, LCoreError> {
+    lcore_char_class(args, "whitespace?", |c| c.is_whitespace())
+}
+
+pub fn lcore_is_ascii(
+    args: &mut Value,
+    symbol_table: &mut Environment,
+) -> Result<Value, LCoreError> {
+    lcore_char_class(args, "ascii?", char::is_ascii)
+}
+
+fn lcore_pad(
+    args: &Value,
+    fn_name: &str,
+    left: bool,
+) -> Result<Value, LCoreError> {
+    let args = args.as_array();
+
+    let (string, width) = match (args.first(), args.get(1)) {
+        (Some(Value::String(s)), Some(Value::Int(w))) => (s, *w),
+        _ => {
+            return Err(LCoreError::ArgumentError(format!(
+                "ArgumentError: \"{}\" takes a String, a width and an optional pad String",
+                fn_name
+            )))
+        }
+    };
+
+    let pad = match args.get(2) {
+        None => " ".to_string(),
+        Some(Value::String(p)) if !p.is_empty() => p.clone(),
+        Some(_) => {
+            return Err(LCoreError::ArgumentError(format!(
+                "ArgumentError: \"{}\" pad must be a non-empty String",
+                fn_name
+            )))
+        }
+    };
+
+    let len = string.chars().count() as i64;
+    if len >= width {
+        return Ok(Value::String(string.clone()));
+    }
+
+    let padding: String =
+        pad.chars().cycle().take((width - len) as usize).collect();
+
+    Ok(Value::String(if left {
+        padding + string
+    } else {
+        string.clone() + &padding
+    }))
+}
+
+/// Pad the start of a String with `pad` (default " ") until it is `width`
+/// characters long. A multi-character pad is repeated and cut off to fit.
+pub fn lcore_pad_left(
+    args: &mut Value,
+    symbol_table: &mut Environment,
+) -> Result<Value, LCoreError> {
+    lcore_pad(args, "pad-left", true)
+}
+
+/// Pad the end of a String with `pad` (default " ") until it is `width`
+/// characters long. A multi-character pad is repeated and cut off to fit.
+pub fn lcore_pad_right(
+    args: &mut Value,
+    symbol_table: &mut Environment,
+) -> Result<Value, LCoreError> {
+    lcore_pad(args, "pad-right", false)
+}
+
+fn lcore_trim_with(
+    args: &Value,
+    fn_name: &str,
+    trim: fn(&str) -> &str,
+) -> Result<Value, LCoreError> {
+    match args.as_array().first() {
+        Some(Value::String(s)) => Ok(Value::String(trim(s).to_string())),
+        _ => Err(LCoreError::ArgumentError(format!(
+            "ArgumentError: \"{}\" takes a String",
+            fn_name
+        ))),
+    }
+}
+
+/// Strip whitespace from both ends of a String.
+pub fn lcore_trim(
+    args: &mut Value,
+    symbol_table: &mut Environment,
+) -> Result<Value, LCoreError> {
+    lcore_trim_with(args, "trim", str::trim)
+}
+
+/// Strip leading whitespace from a String.
+pub fn lcore_trim_start(
+    args: &mut Value,
+    symbol_table: &mut Environment,
+) -> Result<Value, LCoreError> {
+    lcore_trim_with(args, "trim-start", str::trim_start)
+}
+
+/// Strip trailing whitespace from a String.
+pub fn lcore_trim_end(
+    args: &mut Value,
+    symbol_table: &mut Environment,
+) -> Result<Value, LCoreError> {
+    lcore_trim_with(args, "trim-end", str::trim_end)
+}
+
+/// Strip any of the characters in `chars` from both ends of a String:
+/// `(trim-chars "**x**" "*")`.
+pub fn lcore_trim_chars(
+    args: &mut Value,
+    symbol_table: &mut Environment,
+) -> Result<Value, LCoreError> {
+    let args = args.as_array();
+
+    match (args.first(), args.get(1)) {
+        (Some(Value::String(s)), Some(Value::String(chars))) => Ok(
+            Value::String(s.trim_matches(|c| chars.contains(c)).to_string()),
+        ),
+        _ => Err(LCoreError::ArgumentError(format!(
+            "ArgumentError: \"trim-chars\" takes a String and a String of characters"
+        ))),
+    }
+}
+
+// Digits after the point needed to write out the smallest subnormal f64, so
+// any Float is exact at this precision
+const LCORE_MAX_PRECISION: i64 = 1074;
+
+/// Format a Float with a fixed number of digits after the decimal point,
+/// rounding like Rust's `{:.N}`. An Int is accepted and treated as a Float.
+pub fn lcore_format_float(
+    args: &mut Value,
+    symbol_table: &mut Environment,
+) -> Result<Value, LCoreError> {
+    let args = args.as_array();
+    let v = match args.first() {
+        Some(Value::Float(f)) => *f,
+        Some(Value::Int(i)) => *i as f64,
+        _ => {
+            return Err(LCoreError::ArgumentError(format!(
+                "ArgumentError: \"format-float\" takes a Float and an Int"
+            )))
+        }
+    };
+    match args.get(1) {
+        Some(Value::Int(n)) if *n > LCORE_MAX_PRECISION => {
+            Err(LCoreError::ArgumentError(format!(
+                "ArgumentError: Precision must be at most {}, got {}",
+                LCORE_MAX_PRECISION, n
+            )))
+        }
+        Some(Value::Int(n)) if *n >= 0 => {
+            Ok(Value::String(format!("{:.*}", *n as usize, v)))
+        }
+        Some(Value::Int(n)) => Err(LCoreError::ArgumentError(format!(
+            "ArgumentError: Precision must not be negative, got {}",
+            n
+        ))),
+        _ => Err(LCoreError::ArgumentError(format!(
+            "ArgumentError: \"format-float\" takes a Float and an Int"
+        ))),
+    }
+}
+
+/// Render an Int in base 16, 8 or 2 without a `0x`-style prefix. Negative
+/// numbers keep their sign (`-ff`) rather than using two's complement.
+fn lcore_to_base(
+    args: &Value,
+    fn_name: &str,
+    radix: u32,
+) -> Result<Value, LCoreError> {
+    let n = match args.as_array().first() {
+        Some(Value::Int(n)) => *n,
+        _ => {
+            return Err(LCoreError::ArgumentError(format!(
+                "ArgumentError: \"{}\" takes an Int",
+                fn_name
+            )))
+        }
+    };
+
+    let magnitude = n.unsigned_abs();
+    let digits = match radix {
+        16 => format!("{:x}", magnitude),
+        8 => format!("{:o}", magnitude),
+        _ => format!("{:b}", magnitude),
+    };
+    let sign = if n < 0 { "-" } else { "" };
+
+    Ok(Value::String(format!("{}{}", sign, digits)))
+}
+
+/// Lowercase hexadecimal digits of an Int: `(to-hex 255)` gives `"ff"`.
+pub fn lcore_to_hex(
+    args: &mut Value,
+    symbol_table: &mut Environment,
+) -> Result<Value, LCoreError> {
+    lcore_to_base(args, "to-hex", 16)
+}
+
+/// Octal digits of an Int: `(to-oct 8)` gives `"10"`.
+pub fn lcore_to_oct(
+    args: &mut Value,
+    symbol_table: &mut Environment,
+) -> Result<Value, LCoreError> {
+    lcore_to_base(args, "to-oct", 8)
+}
+
+/// Binary digits of an Int: `(to-bin 10)` gives `"1010"`.
+pub fn lcore_to_bin(
+    args: &mut Value,
+    symbol_table: &mut Environment,
+) -> Result<Value, LCoreError> {
+    lcore_to_base(args, "to-bin", 2)
+}
+
+fn lcore_replace_n(
+    args: &Value,
+    fn_name: &str,
+    count: Option<usize>,
+) -> Result<Value, LCoreError> {
+    let args = args.as_array();
+
+    match (args.first(), args.get(1), args.get(2)) {
+        (
+            Some(Value::String(text)),
+            Some(Value::String(pattern)),
+            Some(Value::String(replacement)),
+        ) => {
+            if pattern.is_empty() {
+                return Err(LCoreError::ArgumentError(format!(
+                    "ArgumentError: Empty pattern passed to \"{}\"",
+                    fn_name
+                )));
+            }
+
+            Ok(Value::String(match count {
+                Some(n) => text.replacen(pattern.as_str(), replacement, n),
+                None => text.replace(pattern.as_str(), replacement),
+            }))
+        }
+        _ => Err(LCoreError::ArgumentError(format!(
+            "ArgumentError: \"{}\" takes a String, a pattern and a replacement",
+            fn_name
+        ))),
+    }
+}
+
+/// Replace every occurrence of a pattern within a String.
+pub fn lcore_replace(
+    args: &mut Value,
+    symbol_table: &mut Environment,
+) -> Result<Value, LCoreError> {
+    lcore_replace_n(args, "replace", None)
+}
+
+/// Replace only the first occurrence of a pattern within a String.
+pub fn lcore_replace_first(
+    args: &mut Value,
+    symbol_table: &mut Environment,
+) -> Result<Value, LCoreError> {
+    lcore_replace_n(args, "replace-first", Some(1))
+}
+
+/// Char offsets of every non-overlapping occurrence of a pattern.
+fn lcore_match_offsets(
+    args: &Value,
+    fn_name: &str,
+) -> Result<Vec<Value>, LCoreError> {
+    let args = args.as_array();
+
+    let (text, pattern) = match (args.first(), args.get(1)) {
+        (Some(Value::String(text)), Some(Value::String(pattern))) => {
+            (text, pattern)
+        }
+        _ => {
+            return Err(LCoreError::ArgumentError(format!(
+                "ArgumentError: \"{}\" takes a String and a pattern",
+                fn_name
+            )))
+        }
+    };
+
+    if pattern.is_empty() {
+        return Err(LCoreError::ArgumentError(format!(
+            "ArgumentError: Empty pattern passed to \"{}\"",
+            fn_name
+        )));
+    }
+
+    // match_indices gives byte offsets, so count chars as we go.
+    let mut offsets = vec![];
+    let (mut byte, mut chars) = (0, 0);
+
+    for (i, _) in text.match_indices(pattern.as_str()) {
+        chars += text[byte..i].chars().count();
+        byte = i;
+        offsets.push(Value::Int(chars as i64));
+    }
+
+    Ok(offsets)
+}
+
+/// Every offset at which a pattern occurs in a String, counted in chars
+/// rather than bytes: `(find-all "a,b,c" ",")` gives `[1 3]`.
+pub fn lcore_find_all(
+    args: &mut Value,
+    symbol_table: &mut Environment,
+) -> Result<Value, LCoreError> {
+    Ok(Value::Array(lcore_match_offsets(args, "find-all")?))
+}
+
+/// Number of non-overlapping occurrences of a pattern in a String.
+pub fn lcore_count_matches(
+    args: &mut Value,
+    symbol_table: &mut Environment,
+) -> Result<Value, LCoreError> {
+    let offsets = lcore_match_offsets(args, "count-matches")?;
+
+    Ok(Value::Int(offsets.len() as i64))
+}
+
+fn lcore_regex_args<'a>(
+    args: &'a Value,
+    fn_name: &str,
+    count: usize,
+    whole: bool,
+) -> Result<(Regex, Vec<&'a String>), LCoreError> {
+    let args = args.as_array();
+    let mut strings = vec![];
+
+    for i in 0..count {
+        match args.get(i) {
+            Some(Value::String(s)) => strings.push(s),
+            _ => {
+                return Err(LCoreError::ArgumentError(format!(
+                    "ArgumentError: \"{}\" takes {} String arguments",
+                    fn_name, count
+                )))
+            }
+        }
+    }
+
+    let re = if whole {
+        Regex::new_whole(strings[0])
+    } else {
+        Regex::new(strings[0])
+    };
+
+    match re {
+        Ok(re) => Ok((re, strings[1..].to_vec())),
+        Err(e) => Err(LCoreError::ArgumentError(format!(
+            "ArgumentError: Invalid pattern {:?}: {}",
+            strings[0], e
+        ))),
+    }
+}
+
+/// Check whether a pattern matches the whole of a String.
+pub fn lcore_re_match(
+    args: &mut Value,
+    symbol_table: &mut Environment,
+) -> Result<Value, LCoreError> {
+    let (re, strings) = lcore_regex_args(args, "re-match", 2, true)?;
+    Ok(Value::Boolean(re.is_match(strings[0])))
+}
+
+/// Find the first match of a pattern, returning the whole match followed by
+/// each capture group, or Null if nothing matched.
+pub fn lcore_re_find(
+    args: &mut Value,
+    symbol_table: &mut Environment,
+) -> Result<Value, LCoreError> {
+    let (re, strings) = lcore_regex_args(args, "re-find", 2, false)?;
+
+    match re.captures(strings[0]) {
+        Some(groups) => Ok(Value::Array(
+            groups
+                .into_iter()
+                .map(|g| g.map_or(Value::Null, Value::String))
+                .collect(),
+        )),
+        None => Ok(Value::Null),
+    }
+}
+
+/// Replace every match of a pattern. `$1` (or `${1}` when followed by a
+/// letter or digit) in the replacement refers to a capture group and `$$` is
+/// a literal `$`.
+pub fn lcore_re_replace(
+    args: &mut Value,
+    symbol_table: &mut Environment,
+) -> Result<Value, LCoreError> {
+    let (re, strings) = lcore_regex_args(args, "re-replace", 3, false)?;
+    Ok(Value::String(re.replace_all(strings[0], strings[1])))
+}
+
+pub fn lcore_add(
+    args: &mut Value,
+    symbol_table: &mut Environment,
+) -> Result<Value, LCoreError> {
+    let [a, b] = lcore_take_args(args, "+")?;
+
+    match (a, b) {
+        (Value::Int(a), Value::Int(b)) => Ok(Value::Int(a + b)),
+        (Value::Float(a), Value::Float(b)) => Ok(Value::Float(a + b)),
+        (Value::String(a), Value::String(b)) => {
+            let mut result = a.clone();
+            result.push_str(b);
+            Ok(Value::String(result.to_string()))
+        }
+        (Value::Array(a), Value::Array(b)) => {
+            let mut result = a.clone();
+            result.extend(b.iter().cloned());
+            Ok(Value::Array(result))
+        }
+
+        _ => Err(LCoreError::ArgumentError(format!(
+            "ArgumentError: Invalid operands ({:?} and {:?})",
+            a, b
+        ))),
+    }
+}
+
+pub fn lcore_sub(
+    args: &mut Value,
+    symbol_table: &mut Environment,
+) -> Result<Value, LCoreError> {
+    let [a, b] = lcore_take_args(args, "-")?;
+
+    match (a, b) {
+        (Value::Int(a), Value::Int(b)) => Ok(Value::Int(a - b)),
+        (Value::Float(a), Value::Float(b)) => Ok(Value::Float(a - b)),
+
+        _ => Err(LCoreError::ArgumentError(format!(
+            "ArgumentError: Invalid operands ({:?} and {:?})",
+            a, b
+        ))),
+    }
+}
+
+pub fn lcore_mul(
+    args: &mut Value,
+    symbol_table: &mut Environment,
+) -> Result<Value, LCoreError> {
+    let [a, b] = lcore_take_args(args, "*")?;
+
+    match (a, b) {
+        (Value::Int(a), Value::Int(b)) => Ok(Value::Int(a * b)),
+        (Value::Float(a), Value::Float(b)) => Ok(Value::Float(a * b)),
+        (Value::String(a), Value::Int(b)) => {
+            let mut result = a.clone();
+            for i in 0..*b - 1 {
+                result.push_str(a);
+            }
+            Ok(Value::String(result.to_string()))
+        }
+        (Value::Array(a), Value::Int(b)) => {
+            let aa = a.clone();
+            let mut result = a.clone();
+            for i in 0..*b - 1 {
+                result.extend(aa.iter().cloned());
+            }
+            Ok(Value::Array(result))
+        }
+
+        _ => Err(LCoreError::ArgumentError(format!(
+            "ArgumentError: Invalid operands ({:?} and {:?})",
+            a, b
+        ))),
+    }
+}
+
+pub fn lcore_div(
+    args: &mut Value,
+    symbol_table: &mut Environment,
+) -> Result<Value, LCoreError> {
+    let [a, b] = lcore_take_args(args, "/")?;
+
+    match (a, b) {
+        (Value::Int(a), Value::Int(b)) => Ok(Value::Int(a / b)),
+        (Value::Float(a), Value::Float(b)) => Ok(Value::Float(a / b)),
+
+        _ => Err(LCoreError::ArgumentError(format!(
+            "ArgumentError: Invalid operands ({:?} and {:?})",
+            a, b
+        ))),
+    }
+}
+
+pub fn lcore_exponent(
+    args: &mut Value,
+    symbol_table: &mut Environment,
+) -> Result<Value, LCoreError> {
+    let [a, b] = lcore_take_args(args, "**")?;
+
+    match (a, b) {
+        (Value::Int(a), Value::Int(b)) => {
+            if *b >= 0 {
+                Ok(Value::Int(i64::pow(*a, *b as u32)))
+            } else {
+                Err(LCoreError::ArgumentError(format!(
+                    "ArgumentError: Negative exponent ({})",
+                    b
+                )))
+            }
+        }
+        (Value::Float(a), Value::Float(b)) => {
+            Ok(Value::Float(f64::powf(*a, *b)))
+        }
+
+        _ => Err(LCoreError::ArgumentError(format!(
+            "ArgumentError: Invalid operands ({:?} and {:?})",
             a, b
         ))),
     }
 }
 
-pub fn lcore_exponent(
+/// Read exactly `count` arguments as numbers, coercing Ints to Floats.
+fn lcore_float_args(
+    args: &Value,
+    fn_name: &str,
+    count: usize,
+) -> Result<Vec<f64>, LCoreError> {
+    let args = args.as_array();
+
+    if args.len() != count {
+        return Err(lcore_arity_error(fn_name, count, args.len()));
+    }
+
+    args.iter()
+        .map(|arg| match arg {
+            Value::Int(i) => Ok(*i as f64),
+            Value::Float(f) => Ok(*f),
+            _ => Err(LCoreError::ArgumentError(format!(
+                "ArgumentError: \"{}\" takes numbers, got {:?}",
+                fn_name, arg
+            ))),
+        })
+        .collect()
+}
+
+/// The absolute difference between two numbers. Two Ints give an Int.
+pub fn lcore_abs_diff(
+    args: &mut Value,
+    symbol_table: &mut Environment,
+) -> Result<Value, LCoreError> {
+    if let [Value::Int(a), Value::Int(b)] = args.as_array().as_slice() {
+        return match i64::try_from(a.abs_diff(*b)) {
+            Ok(diff) => Ok(Value::Int(diff)),
+            Err(..) => Err(LCoreError::ArgumentError(format!(
+                "ArgumentError: \"abs-diff\" of {} and {} overflows an Int",
+                a, b
+            ))),
+        };
+    }
+
+    let n = lcore_float_args(args, "abs-diff", 2)?;
+    Ok(Value::Float((n[0] - n[1]).abs()))
+}
+
+/// True if two numbers are within `tol` of each other, for comparing the
+/// results of Float arithmetic: `(approx-eq (+ 0.1 0.2) 0.3 0.000001)`.
+pub fn lcore_approx_eq(
+    args: &mut Value,
+    symbol_table: &mut Environment,
+) -> Result<Value, LCoreError> {
+    let n = lcore_float_args(args, "approx-eq", 3)?;
+    Ok(Value::Boolean((n[0] - n[1]).abs() <= n[2]))
+}
+
+/// Generate an Identifier that is unique within this run, like `prefix__1`.
+pub fn lcore_gensym(
+    args: &mut Value,
+    symbol_table: &mut Environment,
+) -> Result<Value, LCoreError> {
+    let prefix = match args.as_array().first() {
+        Some(Value::String(s)) => s.clone(),
+        None => "g".to_string(),
+        Some(other) => {
+            return Err(LCoreError::ArgumentError(format!(
+                "ArgumentError: \"gensym\" prefix must be a String ({:?})",
+                other
+            )))
+        }
+    };
+
+    let id = GENSYM_COUNTER.fetch_add(1, atomic::Ordering::SeqCst) + 1;
+    Ok(Value::Identifier(format!("{}__{}", prefix, id)))
+}
+
+/// Return the first argument that isn't Null, or Null if they all are. Unlike
+/// a boolean `or`, False is a perfectly good value here.
+pub fn lcore_or_else(
+    args: &mut Value,
+    symbol_table: &mut Environment,
+) -> Result<Value, LCoreError> {
+    Ok(args
+        .as_array()
+        .iter()
+        .find(|v| **v != Value::Null)
+        .cloned()
+        .unwrap_or(Value::Null))
+}
+
+/// `(select cond a b)` returns `a` if `cond` is True and `b` otherwise. Both
+/// branches are evaluated before the call, unlike `if`, so only use it when
+/// neither branch has side effects.
+pub fn lcore_select(
+    args: &mut Value,
+    symbol_table: &mut Environment,
+) -> Result<Value, LCoreError> {
+    let [cond, a, b] = lcore_take_args(args, "select")?;
+
+    match cond {
+        Value::Boolean(cond) => Ok(if *cond { a.clone() } else { b.clone() }),
+        _ => Err(LCoreError::ArgumentError(format!(
+            "ArgumentError: \"select\" condition must be a Boolean, got {:?}",
+            cond
+        ))),
+    }
+}
+
+pub fn lcore_if(
+    args: &mut Value,
+    symbol_table: &mut Environment,
+) -> Result<Value, LCoreError> {
+    let args = args.as_array();
+    let mut vecargs = args.iter();
+    let condition = vecargs.next().unwrap();
+    let block_true = vecargs.next().unwrap();
+
+    let block_false = if args.len() > 2 {
+        vecargs.next().unwrap()
+    } else {
+        &Value::Null
+    };
+
+    if *condition.as_bool() {
+        let element = block_true.as_value();
+        let result = lcore_interpret_array(element, symbol_table);
+        // let mut result = result.ok().unwrap();
+
+        let mut result = match result {
+            Err(err) => return Err(err),
+            Ok(yeah) => yeah,
+        };
+
+        // TODO(pebaz): Check for errors on the result
+
+        if let Value::Array(ref mut r) = result {
+            let ret = r.pop().unwrap();
+            // lcore_print_value(&mut Value::Array(vec![ret]));
+            // println!("  <--");
+            return Ok(ret);
+        }
+    } else {
+        if let Value::Null = block_false {
+        } else {
+            let element = block_false.as_value();
+            let result = lcore_interpret_array(element, symbol_table);
+            //let mut result = result.ok().unwrap();
+
+            let mut result = match result {
+                Err(err) => return Err(err),
+                Ok(yeah) => yeah,
+            };
+
+            if let Value::Array(ref mut r) = result {
+                let ret = r.pop().unwrap();
+                return Ok(ret);
+            }
+        }
+    }
+
+    Ok(Value::Null)
+}
+
+pub fn lcore_sel(
+    args: &mut Value,
+    symbol_table: &mut Environment,
+) -> Result<Value, LCoreError> {
+    let args = args.as_array();
+    let mut vecargs = args.iter();
+    let compare = vecargs.next().unwrap();
+
+    while let (Some(value), Some(code)) = (vecargs.next(), vecargs.next()) {
+        // NOTE(pebaz): Check for `'default` block
+        if let Value::Quote(q) = value {
+            if let Value::Identifier(s) = value.as_value() {
+                if s == "default" {
+                    let result =
+                        lcore_interpret_array(code.as_value(), symbol_table);
+                    /*if let Err(..) = result {
+                        return result;
+                    }
+                    break;*/
+                    match result {
+                        Err(..) => return result,
+                        Ok(mut unwraped_result) => {
+                            if let Value::Array(ref mut r) = unwraped_result {
+                                let ret = r.pop().unwrap();
+                                return Ok(ret);
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        let res = lcore_equals(
+            &mut Value::Array(vec![compare.clone(), value.clone()]),
+            symbol_table,
+        );
+        if let Ok(res) = res {
+            if *res.as_bool() {
+                let result =
+                    lcore_interpret_array(code.as_value(), symbol_table);
+                /*if let Err(..) = result {
+                    return result;
+                }
+                break;*/
+                
+                match result {
+                    Err(..) => return result,
+                    Ok(mut unwraped_result) => {
+                        if let Value::Array(ref mut r) = unwraped_result {
+                            let ret = r.pop().unwrap();
+                            return Ok(ret);
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    Ok(Value::Null)
+}
+
+/// Like `sel`, but each clause is an Array of `[value '[body]]`, compared
+/// using `Value` equality. A clause whose value is `'else` always matches.
+pub fn lcore_case(
+    args: &mut Value,
+    symbol_table: &mut Environment,
+) -> Result<Value, LCoreError> {
+    let args = args.as_array();
+    let mut vecargs = args.iter();
+    let compare = match vecargs.next() {
+        Some(e) => e,
+        None => return Err(lcore_arity_error("case", 1, 0)),
+    };
+
+    for clause in vecargs {
+        let (value, code) = match clause {
+            Value::Array(c) if c.len() == 2 => (&c[0], &c[1]),
+            _ => {
+                return Err(LCoreError::ArgumentError(format!(
+                    "ArgumentError: \"case\" clause must be [value '[body]]"
+                )))
+            }
+        };
+
+        let is_else = match value {
+            Value::Quote(q) => **q == Value::Identifier("else".to_string()),
+            _ => false,
+        };
+
+        if is_else || value == compare {
+            let body = match code {
+                Value::Quote(q) => q,
+                _ => {
+                    return Err(LCoreError::ArgumentError(format!(
+                        "ArgumentError: \"case\" body must be quoted"
+                    )))
+                }
+            };
+
+            let mut result = lcore_interpret_array(body, symbol_table)?;
+            if let Value::Array(ref mut r) = result {
+                return Ok(r.pop().unwrap_or(Value::Null));
+            }
+            return Ok(result);
+        }
+    }
+
+    Ok(Value::Null)
+}
+
+/// Run a quoted Array of code the same way `loop` runs its body, returning
+/// the value of the last expression.
+pub fn lcore_run_body(
+    body: &Value,
+    fn_name: &str,
+    symbol_table: &mut Environment,
+) -> Result<Value, LCoreError> {
+    let mut code = match body {
+        Value::Quote(q) => match &**q {
+            Value::Array(v) => VecDeque::from_iter(v.clone()),
+            _ => {
+                return Err(LCoreError::ArgumentError(format!(
+                    "ArgumentError: \"{}\" body must be a quoted Array",
+                    fn_name
+                )))
+            }
+        },
+        _ => {
+            return Err(LCoreError::ArgumentError(format!(
+                "ArgumentError: \"{}\" body must be a quoted Array",
+                fn_name
+            )))
+        }
+    };
+
+    lcore_interpret(&mut code, symbol_table)
+}
+
+/// Return a Dict snapshot of every visible binding. Native functions can't be
+/// shown meaningfully, so they are represented by the String "<Func>".
+pub fn lcore_env(
+    args: &mut Value,
+    symbol_table: &mut Environment,
+) -> Result<Value, LCoreError> {
+    let mut dict = HashMap::new();
+
+    for (name, value) in symbol_table.bindings() {
+        let value = match value {
+            Value::Func { .. }
+            | Value::Composed(..)
+            | Value::Partial { .. } => Value::String("<Func>".to_string()),
+            other => other,
+        };
+        dict.insert(Value::String(name), value);
+    }
+
+    Ok(Value::Dict(dict))
+}
+
+/// Check whether a name (quoted identifier or String) is defined in any
+/// visible scope.
+pub fn lcore_bound(
+    args: &mut Value,
+    symbol_table: &mut Environment,
+) -> Result<Value, LCoreError> {
+    let name = match args.as_array().first() {
+        Some(Value::String(s)) => s.clone(),
+        Some(Value::Quote(q)) => match &**q {
+            Value::Identifier(s) => s.clone(),
+            other => {
+                return Err(LCoreError::ArgumentError(format!(
+                    "ArgumentError: \"bound?\" takes a quoted name or a String, got {:?}",
+                    other
+                )))
+            }
+        },
+        other => {
+            return Err(LCoreError::ArgumentError(format!(
+                "ArgumentError: \"bound?\" takes a quoted name or a String, got {:?}",
+                other.unwrap_or(&Value::Null)
+            )))
+        }
+    };
+
+    Ok(Value::Boolean(symbol_table.contains_key(name)))
+}
+
+fn lcore_quoted_name(
+    args: &Value,
+    fn_name: &str,
+) -> Result<String, LCoreError> {
+    match args.as_array().first() {
+        Some(Value::Quote(q)) if matches!(**q, Value::Identifier(..)) => {
+            Ok(q.as_identifier().to_string())
+        }
+        _ => Err(LCoreError::ArgumentError(format!(
+            "ArgumentError: \"{}\" takes a quoted name",
+            fn_name
+        ))),
+    }
+}
+
+/// Log every call to the named function (its arguments and return value) to
+/// stderr until `untrace` is called.
+pub fn lcore_trace(
+    args: &mut Value,
+    symbol_table: &mut Environment,
+) -> Result<Value, LCoreError> {
+    let name = lcore_quoted_name(args, "trace")?;
+
+    match symbol_table.get(name.clone()) {
+        Some(Value::Traced { .. }) => Ok(Value::Null),
+        Some(func) if func.is_callable() => {
+            let inner = func.clone();
+            *func = Value::Traced {
+                name,
+                func: Box::new(inner),
+            };
+            Ok(Value::Null)
+        }
+        Some(other) => Err(LCoreError::ArgumentError(format!(
+            "ArgumentError: Cannot trace {:?}",
+            other
+        ))),
+        None => Err(LCoreError::NameError(format!(
+            "NameError: Cannot lookup name: \"{}\"",
+            name
+        ))),
+    }
+}
+
+/// Stop tracing the named function.
+pub fn lcore_untrace(
+    args: &mut Value,
+    symbol_table: &mut Environment,
+) -> Result<Value, LCoreError> {
+    let name = lcore_quoted_name(args, "untrace")?;
+
+    match symbol_table.get(name.clone()) {
+        Some(traced @ Value::Traced { .. }) => {
+            if let Value::Traced { func, .. } = traced.clone() {
+                *traced = *func;
+            }
+            Ok(Value::Null)
+        }
+        Some(..) => Ok(Value::Null),
+        None => Err(LCoreError::NameError(format!(
+            "NameError: Cannot lookup name: \"{}\"",
+            name
+        ))),
+    }
+}
+
+/// Cache the results of the named function by argument list, so repeated
+/// calls with the same (hashable) arguments don't run it again.
+pub fn lcore_memoize(
+    args: &mut Value,
+    symbol_table: &mut Environment,
+) -> Result<Value, LCoreError> {
+    let name = lcore_quoted_name(args, "memoize")?;
+
+    match symbol_table.get(name.clone()) {
+        Some(Value::Memoized { .. }) => Ok(Value::Null),
+        Some(func) if func.is_callable() => {
+            let inner = func.clone();
+            let cache = MEMO_COUNTER.fetch_add(1, atomic::Ordering::SeqCst);
+            symbol_table.insert(
+                name,
+                Value::Memoized {
+                    func: Box::new(inner),
+                    cache,
+                },
+            );
+            Ok(Value::Null)
+        }
+        Some(other) => Err(LCoreError::ArgumentError(format!(
+            "ArgumentError: Cannot memoize {:?}",
+            other
+        ))),
+        None => Err(LCoreError::NameError(format!(
+            "NameError: Cannot lookup name: \"{}\"",
+            name
+        ))),
+    }
+}
+
+/// Protect the named variable from `swap`, `update-in!` and being rebound
+/// with `set` or `defn`. Only the variable is frozen, not its value: copies
+/// bound to other names can still be mutated, and a function parameter of
+/// the same name shadows it with a new, unfrozen binding.
+pub fn lcore_freeze(
+    args: &mut Value,
+    symbol_table: &mut Environment,
+) -> Result<Value, LCoreError> {
+    let name = lcore_quoted_name(args, "freeze")?;
+
+    if symbol_table.freeze(&name) {
+        Ok(Value::Null)
+    } else {
+        Err(LCoreError::NameError(format!(
+            "NameError: Cannot lookup name: \"{}\"",
+            name
+        )))
+    }
+}
+
+/// True if the named variable has been frozen with `freeze`.
+pub fn lcore_is_frozen(
+    args: &mut Value,
+    symbol_table: &mut Environment,
+) -> Result<Value, LCoreError> {
+    let name = lcore_quoted_name(args, "frozen?")?;
+    Ok(Value::Boolean(symbol_table.is_frozen(&name)))
+}
+
+/// Change how deeply LambdaCore function calls may nest before failing with
+/// "recursion limit exceeded". Raising it far past the default risks
+/// overflowing the interpreter's native stack instead.
+pub fn lcore_set_recursion_limit(
+    args: &mut Value,
+    symbol_table: &mut Environment,
+) -> Result<Value, LCoreError> {
+    match args.as_array().first() {
+        Some(Value::Int(limit)) if *limit > 0 => {
+            symbol_table.set_recursion_limit(*limit as usize);
+            Ok(Value::Null)
+        }
+        _ => Err(LCoreError::ArgumentError(format!(
+            "ArgumentError: \"set-recursion-limit\" takes a positive Int"
+        ))),
+    }
+}
+
+/// Run a quoted body, printing how long it took to stderr so that the body's
+/// own output is left untouched. Returns the body's value.
+pub fn lcore_time(
     args: &mut Value,
     symbol_table: &mut Environment,
 ) -> Result<Value, LCoreError> {
-    let mut args = args.as_array().iter();
-    let a = args.next().unwrap();
-    let b = args.next().unwrap();
+    let [body] = lcore_take_args(args, "time")?;
+    let body = body.clone();
 
-    match (a, b) {
-        (Value::Int(a), Value::Int(b)) => {
-            if *b >= 0 {
-                Ok(Value::Int(i64::pow(*a, *b as u32)))
-            } else {
-                Err(LCoreError::ArgumentError(format!(
-                    "ArgumentError: Negative exponent ({})",
-                    b
-                )))
+    let start = Instant::now();
+    let result = lcore_run_body(&body, "time", symbol_table)?;
+    eprintln!("Elapsed time: {:?}", start.elapsed());
+
+    Ok(result)
+}
+
+/// `(try '[body] 'err '[handler])` runs the body and, if it raises an error,
+/// binds the error (see `LCoreError::to_value`) to `err` and runs the handler.
+pub fn lcore_try(
+    args: &mut Value,
+    symbol_table: &mut Environment,
+) -> Result<Value, LCoreError> {
+    let args = args.as_array();
+
+    let (body, name, handler) = match (args.first(), args.get(1), args.get(2))
+    {
+        (Some(body), Some(Value::Quote(name)), Some(handler)) => {
+            match &**name {
+                Value::Identifier(name) => (body, name, handler),
+                _ => {
+                    return Err(LCoreError::ArgumentError(format!(
+                        "ArgumentError: \"try\" takes a body, a quoted name and a handler"
+                    )))
+                }
             }
         }
-        (Value::Float(a), Value::Float(b)) => {
-            Ok(Value::Float(f64::powf(*a, *b)))
+        _ => {
+            return Err(LCoreError::ArgumentError(format!(
+                "ArgumentError: \"try\" takes a body, a quoted name and a handler"
+            )))
         }
+    };
 
-        _ => Err(LCoreError::ArgumentError(format!(
-            "ArgumentError: Invalid operands ({:?} and {:?})",
-            a, b
-        ))),
-    }
+    let err = match lcore_run_body(body, "try", symbol_table) {
+        Err(err) => err,
+        ok => return ok,
+    };
+
+    let err = match err.to_value() {
+        Some(value) => value,
+        None => return Err(err),
+    };
+
+    symbol_table.push();
+    symbol_table.insert(name.clone(), err);
+    let result = lcore_run_body(handler, "try", symbol_table);
+    symbol_table.pop();
+
+    result
 }
 
-pub fn lcore_if(
+/// Register a named test for `run-tests`: `(deftest 'adds '[(assert-eq
+/// (+ 1 2) 3)])`.
+pub fn lcore_deftest(
     args: &mut Value,
     symbol_table: &mut Environment,
 ) -> Result<Value, LCoreError> {
     let args = args.as_array();
-    let mut vecargs = args.iter();
-    let condition = vecargs.next().unwrap();
-    let block_true = vecargs.next().unwrap();
 
-    let block_false = if args.len() > 2 {
-        vecargs.next().unwrap()
-    } else {
-        &Value::Null
+    let (name, body) = match (args.first(), args.get(1)) {
+        (Some(Value::Quote(name)), Some(body @ Value::Quote(..))) => {
+            match &**name {
+                Value::Identifier(name) => (name, body),
+                _ => {
+                    return Err(LCoreError::ArgumentError(format!(
+                        "ArgumentError: \"deftest\" takes a quoted name and a quoted body"
+                    )))
+                }
+            }
+        }
+        _ => {
+            return Err(LCoreError::ArgumentError(format!(
+                "ArgumentError: \"deftest\" takes a quoted name and a quoted body"
+            )))
+        }
     };
 
-    if *condition.as_bool() {
-        let element = block_true.as_value();
-        let result = lcore_interpret_array(element, symbol_table);
-        // let mut result = result.ok().unwrap();
+    symbol_table.add_test(name.clone(), body.clone());
 
-        let mut result = match result {
-            Err(err) => return Err(err),
-            Ok(yeah) => yeah,
+    Ok(Value::Null)
+}
+
+/// Run every test registered with `deftest`, each in its own scope, and
+/// print a line per test plus a summary. Returns the number of failures.
+pub fn lcore_run_tests(
+    args: &mut Value,
+    symbol_table: &mut Environment,
+) -> Result<Value, LCoreError> {
+    let tests = symbol_table.tests();
+    let mut failures = 0;
+
+    for (name, body) in &tests {
+        symbol_table.push();
+        let result = lcore_run_body(body, "deftest", symbol_table);
+        symbol_table.pop();
+
+        let line = match result {
+            Ok(..) | Err(LCoreError::ReturnError(..)) => {
+                format!("PASS {}", name)
+            }
+            Err(err) => {
+                failures += 1;
+                format!("FAIL {}: {}", name, format_error(&err))
+            }
         };
+        writeln!(symbol_table.output(), "{}", line).ok();
+    }
 
-        // TODO(pebaz): Check for errors on the result
+    writeln!(
+        symbol_table.output(),
+        "{} tests, {} failed",
+        tests.len(),
+        failures
+    )
+    .ok();
 
-        if let Value::Array(ref mut r) = result {
-            let ret = r.pop().unwrap();
-            // lcore_print_value(&mut Value::Array(vec![ret]));
-            // println!("  <--");
-            return Ok(ret);
+    Ok(Value::Int(failures))
+}
+
+/// Take the tokens making up the next complete expression: either a single
+/// token or a whole `(...)` call.
+fn lcore_take_form(tokens: &mut VecDeque<Value>) -> Option<VecDeque<Value>> {
+    let mut form = VecDeque::new();
+    let mut depth = 0;
+
+    while let Some(token) = tokens.pop_front() {
+        match token {
+            Value::OpenFunc => depth += 1,
+            Value::CloseFunc => depth -= 1,
+            _ => (),
         }
-    } else {
-        if let Value::Null = block_false {
-        } else {
-            let element = block_false.as_value();
-            let result = lcore_interpret_array(element, symbol_table);
-            //let mut result = result.ok().unwrap();
+        form.push_back(token);
 
-            let mut result = match result {
-                Err(err) => return Err(err),
-                Ok(yeah) => yeah,
-            };
+        if depth == 0 {
+            return Some(form);
+        }
+    }
 
-            if let Value::Array(ref mut r) = result {
-                let ret = r.pop().unwrap();
-                return Ok(ret);
+    None
+}
+
+fn lcore_bind_in_order(
+    bindings: &mut VecDeque<Value>,
+    symbol_table: &mut Environment,
+) -> Result<(), LCoreError> {
+    while let Some(target) = bindings.pop_front() {
+        let name = match &target {
+            Value::Identifier(name) => name.clone(),
+            Value::Quote(q) if matches!(**q, Value::Identifier(..)) => {
+                q.as_identifier().to_string()
+            }
+            // `[a b]` destructures the value, like `set`
+            Value::Array(..) => String::new(),
+            other => {
+                return Err(LCoreError::ArgumentError(format!(
+                    "ArgumentError: \"let*\" expected a name, got {:?}",
+                    other
+                )))
+            }
+        };
+
+        let mut expr = match lcore_take_form(bindings) {
+            Some(expr) => expr,
+            None => {
+                return Err(LCoreError::ArgumentError(format!(
+                    "ArgumentError: \"let*\" has no value for {:?}",
+                    name
+                )))
+            }
+        };
+
+        let value = lcore_interpret(&mut expr, symbol_table)?;
+
+        match target {
+            Value::Array(names) => {
+                for (name, value) in lcore_destructure(&names, &value)? {
+                    symbol_table.define(name, value);
+                }
             }
+            _ => symbol_table.define(name, value),
         }
     }
 
-    Ok(Value::Null)
+    Ok(())
 }
 
-pub fn lcore_sel(
+/// `(let* '[name expr ...] '[body])` binds each name in a new scope, in
+/// order, so later expressions can use earlier names. The bindings must be
+/// quoted so each expression is only evaluated once the names before it exist.
+pub fn lcore_let_star(
     args: &mut Value,
     symbol_table: &mut Environment,
 ) -> Result<Value, LCoreError> {
     let args = args.as_array();
-    let mut vecargs = args.iter();
-    let compare = vecargs.next().unwrap();
 
-    while let (Some(value), Some(code)) = (vecargs.next(), vecargs.next()) {
-        // NOTE(pebaz): Check for `'default` block
-        if let Value::Quote(q) = value {
-            if let Value::Identifier(s) = value.as_value() {
-                if s == "default" {
-                    let result =
-                        lcore_interpret_array(code.as_value(), symbol_table);
-                    /*if let Err(..) = result {
-                        return result;
-                    }
-                    break;*/
-                    match result {
-                        Err(..) => return result,
-                        Ok(mut unwraped_result) => {
-                            if let Value::Array(ref mut r) = unwraped_result {
-                                let ret = r.pop().unwrap();
-                                return Ok(ret);
-                            }
-                        }
-                    }
-                }
+    let (mut bindings, body) = match (args.first(), args.get(1)) {
+        (Some(Value::Quote(q)), Some(body)) => match &**q {
+            Value::Array(b) => (VecDeque::from_iter(b.clone()), body),
+            _ => {
+                return Err(LCoreError::ArgumentError(format!(
+                    "ArgumentError: \"let*\" bindings must be a quoted Array"
+                )))
             }
+        },
+        _ => {
+            return Err(LCoreError::ArgumentError(format!(
+                "ArgumentError: \"let*\" takes quoted bindings and a body"
+            )))
         }
+    };
 
-        let res = lcore_equals(
-            &mut Value::Array(vec![compare.clone(), value.clone()]),
-            symbol_table,
-        );
-        if let Ok(res) = res {
-            if *res.as_bool() {
-                let result =
-                    lcore_interpret_array(code.as_value(), symbol_table);
-                /*if let Err(..) = result {
-                    return result;
+    symbol_table.push();
+    let result = lcore_bind_in_order(&mut bindings, symbol_table)
+        .and_then(|_| lcore_run_body(body, "let*", symbol_table));
+    symbol_table.pop();
+
+    result
+}
+
+fn lcore_one_armed_if(
+    args: &mut Value,
+    symbol_table: &mut Environment,
+    fn_name: &str,
+    run_when: bool,
+) -> Result<Value, LCoreError> {
+    let args = args.as_array();
+    if args.len() != 2 {
+        return Err(lcore_arity_error(fn_name, 2, args.len()));
+    }
+
+    let condition = match &args[0] {
+        Value::Boolean(b) => *b,
+        other => {
+            return Err(LCoreError::ArgumentError(format!(
+                "ArgumentError: Not a boolean ({:?})",
+                other
+            )))
+        }
+    };
+
+    if condition == run_when {
+        lcore_run_body(&args[1], fn_name, symbol_table)
+    } else {
+        Ok(Value::Null)
+    }
+}
+
+pub fn lcore_when(
+    args: &mut Value,
+    symbol_table: &mut Environment,
+) -> Result<Value, LCoreError> {
+    lcore_one_armed_if(args, symbol_table, "when", true)
+}
+
+pub fn lcore_unless(
+    args: &mut Value,
+    symbol_table: &mut Environment,
+) -> Result<Value, LCoreError> {
+    lcore_one_armed_if(args, symbol_table, "unless", false)
+}
+
+pub fn lcore_check_key(key: &Value) -> Result<(), LCoreError> {
+    if key.is_hashable() {
+        Ok(())
+    } else {
+        Err(LCoreError::ArgumentError(format!(
+            "ArgumentError: {:?} cannot be used as a Dict key",
+            key
+        )))
+    }
+}
+
+fn lcore_check_callable(func: &Value) -> Result<(), LCoreError> {
+    if func.is_callable() {
+        Ok(())
+    } else {
+        Err(LCoreError::ArgumentError(format!(
+            "ArgumentError: {:?} is not callable",
+            func
+        )))
+    }
+}
+
+/// Call a predicate on a single value, requiring it to return a Boolean.
+fn lcore_predicate(
+    pred: &Value,
+    value: &Value,
+    symbol_table: &mut Environment,
+) -> Result<bool, LCoreError> {
+    match lcore_call(
+        pred,
+        &mut Value::Array(vec![value.clone()]),
+        symbol_table,
+    )? {
+        Value::Boolean(b) => Ok(b),
+        other => Err(LCoreError::ArgumentError(format!(
+            "ArgumentError: Predicate returned non-boolean ({:?})",
+            other
+        ))),
+    }
+}
+
+/// Return a new function that applies the given functions right-to-left:
+/// `((compose f g) x)` is `(f (g x))`.
+pub fn lcore_compose(
+    args: &mut Value,
+    symbol_table: &mut Environment,
+) -> Result<Value, LCoreError> {
+    let funcs = args.as_array();
+
+    if funcs.len() < 2 {
+        return Err(lcore_arity_error("compose", 2, funcs.len()));
+    }
+
+    for func in funcs {
+        lcore_check_callable(func)?;
+    }
+
+    Ok(Value::Composed(funcs.clone()))
+}
+
+/// Return a new function that calls `func` with the given leading arguments
+/// followed by the arguments it is called with: `((partial + 10) 5)` is 15.
+pub fn lcore_partial(
+    args: &mut Value,
+    symbol_table: &mut Environment,
+) -> Result<Value, LCoreError> {
+    let args = args.as_array();
+
+    let func = match args.first() {
+        Some(f) => f,
+        None => return Err(lcore_arity_error("partial", 1, 0)),
+    };
+
+    lcore_check_callable(func)?;
+
+    Ok(Value::Partial {
+        func: Box::new(func.clone()),
+        args: args[1..].to_vec(),
+    })
+}
+
+/// Thread a value through each function from left to right:
+/// `(pipe x f g h)` is `(h (g (f x)))`.
+pub fn lcore_pipe(
+    args: &mut Value,
+    symbol_table: &mut Environment,
+) -> Result<Value, LCoreError> {
+    let args = args.as_array();
+
+    if args.len() < 2 {
+        return Err(lcore_arity_error("pipe", 2, args.len()));
+    }
+
+    let mut result = args[0].clone();
+
+    for func in &args[1..] {
+        lcore_check_callable(func)?;
+
+        result =
+            lcore_call(func, &mut Value::Array(vec![result]), symbol_table)?;
+    }
+
+    Ok(result)
+}
+
+/// Get the function and argument tokens out of a single quoted call form
+/// like `'(f x)`. A quoted Identifier like `'f` is a call with no arguments.
+fn lcore_call_form(form: &Value) -> Option<Vec<Value>> {
+    match form {
+        Value::Quote(q) => match &**q {
+            Value::Identifier(..) => Some(vec![(**q).clone()]),
+            Value::Array(tokens) => {
+                if tokens.len() < 3
+                    || !matches!(tokens[0], Value::OpenFunc)
+                    || !matches!(tokens[tokens.len() - 1], Value::CloseFunc)
+                {
+                    return None;
                 }
-                break;*/
-                
-                match result {
-                    Err(..) => return result,
-                    Ok(mut unwraped_result) => {
-                        if let Value::Array(ref mut r) = unwraped_result {
-                            let ret = r.pop().unwrap();
-                            return Ok(ret);
-                        }
+
+                // The first `(` must be closed by the last `)`
+                let mut depth = 0;
+                for (i, token) in tokens.iter().enumerate() {
+                    match token {
+                        Value::OpenFunc => depth += 1,
+                        Value::CloseFunc => depth -= 1,
+                        _ => (),
+                    }
+                    if depth == 0 && i < tokens.len() - 1 {
+                        return None;
                     }
                 }
+
+                Some(tokens[1..tokens.len() - 1].to_vec())
+            }
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Thread a value through quoted call forms, inserting it as either the
+/// first or the last argument of each call.
+fn lcore_thread(
+    args: &mut Value,
+    symbol_table: &mut Environment,
+    fn_name: &str,
+    first: bool,
+) -> Result<Value, LCoreError> {
+    let args = args.as_array();
+
+    let mut result = match args.first() {
+        Some(e) => e.clone(),
+        None => return Err(lcore_arity_error(fn_name, 1, 0)),
+    };
+
+    for form in &args[1..] {
+        let call = match lcore_call_form(form) {
+            Some(c) => c,
+            None => {
+                return Err(LCoreError::ArgumentError(format!(
+                    "ArgumentError: \"{}\" expects quoted calls like '(f x)",
+                    fn_name
+                )))
             }
+        };
+
+        // Evaluate the function and its arguments like an Array literal
+        let mut call_args =
+            match lcore_interpret_array(&Value::Array(call), symbol_table)? {
+                Value::Array(v) => v,
+                _ => unreachable!(),
+            };
+        let func = call_args.remove(0);
+        lcore_check_callable(&func)?;
+
+        if first {
+            call_args.insert(0, result);
+        } else {
+            call_args.push(result);
         }
+
+        result =
+            lcore_call(&func, &mut Value::Array(call_args), symbol_table)?;
     }
 
-    Ok(Value::Null)
+    Ok(result)
+}
+
+/// `(-> x '(f a) '(g b))` is `(g (f x a) b)`.
+pub fn lcore_thread_first(
+    args: &mut Value,
+    symbol_table: &mut Environment,
+) -> Result<Value, LCoreError> {
+    lcore_thread(args, symbol_table, "->", true)
+}
+
+/// `(->> x '(f a) '(g b))` is `(g b (f a x))`.
+pub fn lcore_thread_last(
+    args: &mut Value,
+    symbol_table: &mut Environment,
+) -> Result<Value, LCoreError> {
+    lcore_thread(args, symbol_table, "->>", false)
 }
 
 pub fn lcore_return(
     args: &mut Value,
     symbol_table: &mut Environment,
 ) -> Result<Value, LCoreError> {
-    let args = args.as_array();
-    let mut vecargs = args.iter();
-    let value = vecargs.next().unwrap();
-    symbol_table.push_ret(value.clone());
-    // Ok(Value::Null)
-    // Err(LCoreError::LambdaCoreError(format!("BREAK")))
-    // LCoreError::Return(value.clone())
-    LCoreError::Return()
+    let value = args.as_array().first().cloned().unwrap_or(Value::Null);
+    LCoreError::Return(value)
 }
 
 pub fn lcore_break(
@@ -1015,19 +5049,355 @@ pub fn lcore_break(
 pub fn import_builtins(symbol_table: &mut Environment) {
     symbol_table.insert("print".to_string(), Value::Func { f: lcore_print });
     symbol_table.insert("prin".to_string(), Value::Func { f: lcore_prin });
+    symbol_table.insert(
+        "with-output-to-string".to_string(),
+        Value::Func {
+            f: lcore_with_output_to_string,
+        },
+    );
+    symbol_table.insert("eprint".to_string(), Value::Func { f: lcore_eprint });
+    symbol_table
+        .insert("eprintln".to_string(), Value::Func { f: lcore_eprintln });
     symbol_table.insert("quit".to_string(), Value::Func { f: lcore_quit });
     symbol_table.insert("exit".to_string(), Value::Func { f: lcore_quit });
     symbol_table.insert("set".to_string(), Value::Func { f: lcore_set });
+    symbol_table.insert(
+        "def-global".to_string(),
+        Value::Func {
+            f: lcore_def_global,
+        },
+    );
     symbol_table.insert("loop".to_string(), Value::Func { f: lcore_loop });
     symbol_table.insert("defn".to_string(), Value::Func { f: lcore_defn });
+    symbol_table.insert("doc".to_string(), Value::Func { f: lcore_doc });
     symbol_table.insert("get".to_string(), Value::Func { f: lcore_get });
     symbol_table.insert("dict".to_string(), Value::Func { f: lcore_dict });
     symbol_table.insert("len".to_string(), Value::Func { f: lcore_len });
+    symbol_table.insert("empty?".to_string(), Value::Func { f: lcore_empty });
+    symbol_table
+        .insert("not-empty?".to_string(), Value::Func { f: lcore_not_empty });
     symbol_table
         .insert(String::from("import"), Value::Func { f: lcore_import });
     symbol_table.insert(String::from("swap"), Value::Func { f: lcore_swap });
+    symbol_table
+        .insert("update-in!".to_string(), Value::Func { f: lcore_update_in });
+    symbol_table.insert("copy".to_string(), Value::Func { f: lcore_copy });
+    symbol_table
+        .insert("deep-size".to_string(), Value::Func { f: lcore_deep_size });
+    symbol_table.insert("depth".to_string(), Value::Func { f: lcore_depth });
+    symbol_table.insert("set-in".to_string(), Value::Func { f: lcore_set_in });
+    symbol_table
+        .insert("assoc-in".to_string(), Value::Func { f: lcore_assoc_in });
+    symbol_table.insert("get-in".to_string(), Value::Func { f: lcore_get_in });
+    symbol_table
+        .insert("get-path".to_string(), Value::Func { f: lcore_get_path });
+    symbol_table.insert("chunk".to_string(), Value::Func { f: lcore_chunk });
+    symbol_table.insert(
+        "interleave".to_string(),
+        Value::Func {
+            f: lcore_interleave,
+        },
+    );
+    symbol_table
+        .insert("flatten-1".to_string(), Value::Func { f: lcore_flatten_1 });
+    symbol_table.insert(
+        "index-of".to_string(),
+        Value::Func {
+            f: lcore_array_index_of,
+        },
+    );
+    symbol_table.insert(
+        "contains?".to_string(),
+        Value::Func {
+            f: lcore_contains_q,
+        },
+    );
+    symbol_table
+        .insert("first-n".to_string(), Value::Func { f: lcore_first_n });
+    symbol_table.insert("last-n".to_string(), Value::Func { f: lcore_last_n });
+    symbol_table
+        .insert("every-nth".to_string(), Value::Func { f: lcore_every_nth });
+    symbol_table.insert("rotate".to_string(), Value::Func { f: lcore_rotate });
+    symbol_table
+        .insert("split-at".to_string(), Value::Func { f: lcore_split_at });
+    symbol_table.insert("nth".to_string(), Value::Func { f: lcore_nth });
+    symbol_table.insert("last".to_string(), Value::Func { f: lcore_last });
+    symbol_table.insert("insert".to_string(), Value::Func { f: lcore_insert });
+    symbol_table
+        .insert("remove-at".to_string(), Value::Func { f: lcore_remove_at });
+    symbol_table
+        .insert("partition".to_string(), Value::Func { f: lcore_partition });
+    symbol_table
+        .insert("flat-map".to_string(), Value::Func { f: lcore_flat_map });
+    symbol_table.insert("scan".to_string(), Value::Func { f: lcore_scan });
+    symbol_table
+        .insert("iterate".to_string(), Value::Func { f: lcore_iterate });
+    symbol_table
+        .insert("group-by".to_string(), Value::Func { f: lcore_group_by });
+    symbol_table.insert(
+        "frequencies".to_string(),
+        Value::Func {
+            f: lcore_frequencies,
+        },
+    );
+    symbol_table.insert(
+        "map-values".to_string(),
+        Value::Func {
+            f: lcore_map_values,
+        },
+    );
+    symbol_table
+        .insert("map-keys".to_string(), Value::Func { f: lcore_map_keys });
+    symbol_table
+        .insert("reduce-kv".to_string(), Value::Func { f: lcore_reduce_kv });
+    symbol_table
+        .insert("read-dir".to_string(), Value::Func { f: lcore_read_dir });
+    symbol_table
+        .insert("abs-path".to_string(), Value::Func { f: lcore_abs_path });
+    symbol_table
+        .insert("exists?".to_string(), Value::Func { f: lcore_exists });
+    symbol_table
+        .insert("is-file?".to_string(), Value::Func { f: lcore_is_file });
+    symbol_table
+        .insert("is-dir?".to_string(), Value::Func { f: lcore_is_dir });
+    symbol_table.insert(
+        "append-file".to_string(),
+        Value::Func {
+            f: lcore_append_file,
+        },
+    );
+    symbol_table.insert("system".to_string(), Value::Func { f: lcore_system });
+    symbol_table.insert(
+        "split-lines".to_string(),
+        Value::Func {
+            f: lcore_split_lines,
+        },
+    );
+    symbol_table.insert(
+        "read-lines".to_string(),
+        Value::Func {
+            f: lcore_read_lines,
+        },
+    );
+    symbol_table.insert(
+        "read-bytes".to_string(),
+        Value::Func {
+            f: lcore_read_bytes,
+        },
+    );
+    symbol_table.insert(
+        "bytes->string".to_string(),
+        Value::Func {
+            f: lcore_bytes_to_string,
+        },
+    );
+    symbol_table.insert(
+        "hex-encode".to_string(),
+        Value::Func {
+            f: lcore_hex_encode,
+        },
+    );
+    symbol_table.insert(
+        "hex-decode".to_string(),
+        Value::Func {
+            f: lcore_hex_decode,
+        },
+    );
+    symbol_table.insert(
+        "base64-encode".to_string(),
+        Value::Func {
+            f: lcore_base64_encode,
+        },
+    );
+    symbol_table.insert(
+        "base64-decode".to_string(),
+        Value::Func {
+            f: lcore_base64_decode,
+        },
+    );
+    symbol_table.insert("loc".to_string(), Value::Func { f: lcore_loc });
+    symbol_table.insert("time".to_string(), Value::Func { f: lcore_time });
+    symbol_table.insert("env".to_string(), Value::Func { f: lcore_env });
+    symbol_table.insert("bound?".to_string(), Value::Func { f: lcore_bound });
+    symbol_table
+        .insert("describe".to_string(), Value::Func { f: lcore_describe });
+    symbol_table.insert("trace".to_string(), Value::Func { f: lcore_trace });
+    symbol_table
+        .insert("untrace".to_string(), Value::Func { f: lcore_untrace });
+    symbol_table
+        .insert("memoize".to_string(), Value::Func { f: lcore_memoize });
+    symbol_table.insert("freeze".to_string(), Value::Func { f: lcore_freeze });
+    symbol_table
+        .insert("frozen?".to_string(), Value::Func { f: lcore_is_frozen });
+    symbol_table.insert(
+        "set-recursion-limit".to_string(),
+        Value::Func {
+            f: lcore_set_recursion_limit,
+        },
+    );
+    symbol_table.insert("try".to_string(), Value::Func { f: lcore_try });
+    symbol_table.insert("let*".to_string(), Value::Func { f: lcore_let_star });
+    symbol_table
+        .insert("hash-of".to_string(), Value::Func { f: lcore_hash_of });
+    symbol_table.insert(
+        "select-keys".to_string(),
+        Value::Func {
+            f: lcore_select_keys,
+        },
+    );
+    symbol_table.insert(
+        "dict->pairs".to_string(),
+        Value::Func {
+            f: lcore_dict_to_pairs,
+        },
+    );
+    symbol_table.insert(
+        "pairs->dict".to_string(),
+        Value::Func {
+            f: lcore_pairs_to_dict,
+        },
+    );
+    symbol_table.insert(
+        "array->dict-indexed".to_string(),
+        Value::Func {
+            f: lcore_array_to_dict_indexed,
+        },
+    );
+    symbol_table.insert(
+        "zip-to-dict".to_string(),
+        Value::Func {
+            f: lcore_zip_to_dict,
+        },
+    );
+    symbol_table
+        .insert("conform".to_string(), Value::Func { f: lcore_conform });
+    symbol_table.insert("diff".to_string(), Value::Func { f: lcore_diff });
+    symbol_table.insert("min-by".to_string(), Value::Func { f: lcore_min_by });
+    symbol_table.insert("max-by".to_string(), Value::Func { f: lcore_max_by });
+    symbol_table
+        .insert("compare".to_string(), Value::Func { f: lcore_compare });
+    symbol_table.insert(
+        "sort-unique".to_string(),
+        Value::Func {
+            f: lcore_sort_unique,
+        },
+    );
+    symbol_table.insert(
+        "merge-sorted".to_string(),
+        Value::Func {
+            f: lcore_merge_sorted,
+        },
+    );
+    symbol_table
+        .insert("defstruct".to_string(), Value::Func { f: lcore_defstruct });
+    symbol_table
+        .insert("get-field".to_string(), Value::Func { f: lcore_get_field });
+    symbol_table
+        .insert("set-field".to_string(), Value::Func { f: lcore_set_field });
 
     symbol_table.insert("to-str".to_string(), Value::Func { f: lcore_to_str });
+    symbol_table.insert("repr".to_string(), Value::Func { f: lcore_repr });
+    symbol_table.insert("read".to_string(), Value::Func { f: lcore_read });
+    symbol_table
+        .insert("parse-ok?".to_string(), Value::Func { f: lcore_parse_ok });
+    symbol_table.insert(
+        "parse-error".to_string(),
+        Value::Func {
+            f: lcore_parse_error_of,
+        },
+    );
+    symbol_table.insert(
+        "string->chars".to_string(),
+        Value::Func {
+            f: lcore_string_to_chars,
+        },
+    );
+    symbol_table.insert(
+        "chars->string".to_string(),
+        Value::Func {
+            f: lcore_chars_to_string,
+        },
+    );
+    symbol_table
+        .insert("pad-left".to_string(), Value::Func { f: lcore_pad_left });
+    symbol_table
+        .insert("pad-right".to_string(), Value::Func { f: lcore_pad_right });
+    symbol_table.insert("trim".to_string(), Value::Func { f: lcore_trim });
+    symbol_table.insert(
+        "trim-start".to_string(),
+        Value::Func {
+            f: lcore_trim_start,
+        },
+    );
+    symbol_table
+        .insert("trim-end".to_string(), Value::Func { f: lcore_trim_end });
+    symbol_table.insert(
+        "trim-chars".to_string(),
+        Value::Func {
+            f: lcore_trim_chars,
+        },
+    );
+    symbol_table.insert(
+        "format-float".to_string(),
+        Value::Func {
+            f: lcore_format_float,
+        },
+    );
+    symbol_table.insert("to-hex".to_string(), Value::Func { f: lcore_to_hex });
+    symbol_table.insert("to-oct".to_string(), Value::Func { f: lcore_to_oct });
+    symbol_table.insert("to-bin".to_string(), Value::Func { f: lcore_to_bin });
+    symbol_table.insert("chr".to_string(), Value::Func { f: lcore_chr });
+    symbol_table.insert("ord".to_string(), Value::Func { f: lcore_ord });
+    symbol_table.insert(
+        "char-count".to_string(),
+        Value::Func {
+            f: lcore_char_count,
+        },
+    );
+    symbol_table.insert(
+        "string-replace-char".to_string(),
+        Value::Func {
+            f: lcore_string_replace_char,
+        },
+    );
+    symbol_table
+        .insert("digit?".to_string(), Value::Func { f: lcore_is_digit });
+    symbol_table
+        .insert("alpha?".to_string(), Value::Func { f: lcore_is_alpha });
+    symbol_table.insert(
+        "whitespace?".to_string(),
+        Value::Func {
+            f: lcore_is_whitespace,
+        },
+    );
+    symbol_table
+        .insert("ascii?".to_string(), Value::Func { f: lcore_is_ascii });
+    symbol_table
+        .insert("replace".to_string(), Value::Func { f: lcore_replace });
+    symbol_table.insert(
+        "replace-first".to_string(),
+        Value::Func {
+            f: lcore_replace_first,
+        },
+    );
+    symbol_table
+        .insert("find-all".to_string(), Value::Func { f: lcore_find_all });
+    symbol_table.insert(
+        "count-matches".to_string(),
+        Value::Func {
+            f: lcore_count_matches,
+        },
+    );
+    symbol_table
+        .insert("re-match".to_string(), Value::Func { f: lcore_re_match });
+    symbol_table
+        .insert("re-find".to_string(), Value::Func { f: lcore_re_find });
+    symbol_table.insert(
+        "re-replace".to_string(),
+        Value::Func {
+            f: lcore_re_replace,
+        },
+    );
     symbol_table.insert("=".to_string(), Value::Func { f: lcore_equals });
     symbol_table.insert(
         "!=".to_string(),
@@ -1035,6 +5405,24 @@ pub fn import_builtins(symbol_table: &mut Environment) {
             f: lcore_not_equals,
         },
     );
+    symbol_table.insert(
+        "=i".to_string(),
+        Value::Func {
+            f: lcore_equals_ignore_case,
+        },
+    );
+    symbol_table.insert(
+        "equals-ignore-case".to_string(),
+        Value::Func {
+            f: lcore_equals_ignore_case,
+        },
+    );
+    symbol_table
+        .insert("assert-eq".to_string(), Value::Func { f: lcore_assert_eq });
+    symbol_table
+        .insert("deftest".to_string(), Value::Func { f: lcore_deftest });
+    symbol_table
+        .insert("run-tests".to_string(), Value::Func { f: lcore_run_tests });
     symbol_table.insert(
         "or".to_string(),
         Value::Func {
@@ -1047,20 +5435,76 @@ pub fn import_builtins(symbol_table: &mut Environment) {
             f: lcore_logical_and,
         },
     );
+    symbol_table.insert("or*".to_string(), Value::Func { f: lcore_short_or });
+    symbol_table
+        .insert("and*".to_string(), Value::Func { f: lcore_short_and });
     symbol_table.insert(
         "not".to_string(),
         Value::Func {
             f: lcore_logical_not,
         },
     );
+    symbol_table
+        .insert("truthy?".to_string(), Value::Func { f: lcore_truthy });
+    symbol_table
+        .insert("number?".to_string(), Value::Func { f: lcore_is_number });
+    symbol_table.insert(
+        "numeric-string?".to_string(),
+        Value::Func {
+            f: lcore_is_numeric_string,
+        },
+    );
     symbol_table.insert("+".to_string(), Value::Func { f: lcore_add });
     symbol_table.insert("-".to_string(), Value::Func { f: lcore_sub });
     symbol_table.insert("*".to_string(), Value::Func { f: lcore_mul });
     symbol_table.insert("/".to_string(), Value::Func { f: lcore_div });
     symbol_table.insert("**".to_string(), Value::Func { f: lcore_exponent });
+    symbol_table
+        .insert("abs-diff".to_string(), Value::Func { f: lcore_abs_diff });
+    symbol_table
+        .insert("approx-eq".to_string(), Value::Func { f: lcore_approx_eq });
+    symbol_table.insert("gensym".to_string(), Value::Func { f: lcore_gensym });
     symbol_table.insert("if".to_string(), Value::Func { f: lcore_if });
     symbol_table.insert("sel".to_string(), Value::Func { f: lcore_sel });
+    symbol_table.insert("case".to_string(), Value::Func { f: lcore_case });
+    symbol_table.insert("when".to_string(), Value::Func { f: lcore_when });
+    symbol_table.insert("unless".to_string(), Value::Func { f: lcore_unless });
+    symbol_table
+        .insert("or-else".to_string(), Value::Func { f: lcore_or_else });
+    symbol_table.insert("select".to_string(), Value::Func { f: lcore_select });
+    symbol_table
+        .insert("compose".to_string(), Value::Func { f: lcore_compose });
+    symbol_table
+        .insert("partial".to_string(), Value::Func { f: lcore_partial });
+    symbol_table.insert("pipe".to_string(), Value::Func { f: lcore_pipe });
+    symbol_table.insert(
+        "->".to_string(),
+        Value::Func {
+            f: lcore_thread_first,
+        },
+    );
+    symbol_table.insert(
+        "thread-first".to_string(),
+        Value::Func {
+            f: lcore_thread_first,
+        },
+    );
+    symbol_table.insert(
+        "->>".to_string(),
+        Value::Func {
+            f: lcore_thread_last,
+        },
+    );
+    symbol_table.insert(
+        "thread-last".to_string(),
+        Value::Func {
+            f: lcore_thread_last,
+        },
+    );
     symbol_table.insert("ret".to_string(), Value::Func { f: lcore_return });
+    symbol_table.insert("return".to_string(), Value::Func { f: lcore_return });
     symbol_table.insert("break".to_string(), Value::Func { f: lcore_break });
     symbol_table.insert("<".to_string(), Value::Func { f: lcore_less_than });
+
+    lcore_register_func_names(symbol_table);
 }
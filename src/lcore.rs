@@ -7,13 +7,17 @@ use pest::error::Error;
 use pest::error::ErrorVariant::ParsingError;
 use pest::iterators::Pair;
 use pest::Parser;
+use std::any::Any;
+use std::cell::RefCell;
 use std::cmp::{Eq, PartialEq};
-use std::collections::{HashMap, VecDeque};
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::fmt;
 use std::fs;
 use std::hash::{Hash, Hasher};
 use std::io::{self, Write};
 use std::iter::FromIterator;
+use std::mem;
+use std::path::PathBuf;
 use std::process::exit;
 use std::str::FromStr;
 
@@ -33,17 +37,49 @@ pub enum Value {
     Int(i64),
     Float(f64),
     String(String),
+    Char(char),
     Array(Vec<Value>),
+    /// Raw binary data, as read by `read-bytes`
+    Bytes(Vec<u8>),
     Func {
         f: fn(&mut Value, &mut Environment) -> Result<Value, LCoreError>,
     },
     Quote(Box<Value>),
     Dict(HashMap<Value, Value>),
+    Composed(Vec<Value>),
+    Partial {
+        func: Box<Value>,
+        args: Vec<Value>,
+    },
 
-    // TODO(pebaz):
     Struct {
         name: String,
-        fields: Vec<Value>,
+        fields: Vec<(String, Value)>,
+    },
+    StructType {
+        name: String,
+        fields: Vec<String>,
+    },
+
+    /// A function wrapped by `trace` that logs its calls to stderr
+    Traced {
+        name: String,
+        func: Box<Value>,
+    },
+
+    /// A function wrapped by `memoize`. Its results are cached by argument
+    /// list in the Environment, under the unique id `cache`, so every copy of
+    /// the function shares the same cache.
+    Memoized {
+        func: Box<Value>,
+        cache: usize,
+    },
+
+    /// A function looked up as `alias/name` from a module imported under an
+    /// alias. It runs with that module's bindings in scope.
+    Namespaced {
+        namespace: String,
+        func: Box<Value>,
     },
 
     // Lexical Values
@@ -120,15 +156,95 @@ impl Value {
             _ => unreachable!(),
         }
     }
+
+    /// Only scalar values can be used as Dict keys.
+    pub fn is_hashable(&self) -> bool {
+        match self {
+            Value::Null
+            | Value::Boolean(..)
+            | Value::Int(..)
+            | Value::Float(..)
+            | Value::String(..)
+            | Value::Char(..)
+            | Value::Bytes(..)
+            | Value::Identifier(..) => true,
+            Value::Quote(q) => q.is_hashable(),
+            _ => false,
+        }
+    }
+
+    /// The loose truthiness used by `truthy?`: Null, False, zero, the empty
+    /// String and empty collections are falsy and everything else is truthy.
+    /// Conditions in `if` and friends still require a real Boolean.
+    pub fn is_truthy(&self) -> bool {
+        match self {
+            Value::Null => false,
+            Value::Boolean(b) => *b,
+            Value::Int(i) => *i != 0,
+            Value::Float(f) => *f != 0.0,
+            Value::String(s) => !s.is_empty(),
+            Value::Array(a) => !a.is_empty(),
+            Value::Bytes(b) => !b.is_empty(),
+            Value::Dict(d) => !d.is_empty(),
+            _ => true,
+        }
+    }
+
+    /// A quoted name such as a function parameter (`'x`).
+    pub fn is_quoted_identifier(&self) -> bool {
+        match self {
+            Value::Quote(q) => matches!(**q, Value::Identifier(..)),
+            _ => false,
+        }
+    }
+
+    /// Native functions, compositions, and LambdaCore functions (stored by
+    /// `defn` as `[['args] [body] doc]`, where doc is a String or Null) can
+    /// all be called. Other Arrays, like `[[1] [2] 3]`, are plain data.
+    pub fn is_callable(&self) -> bool {
+        match self {
+            Value::Func { .. }
+            | Value::Composed(..)
+            | Value::Partial { .. }
+            | Value::StructType { .. }
+            | Value::Traced { .. }
+            | Value::Memoized { .. }
+            | Value::Namespaced { .. } => true,
+            Value::Array(a) => match a.as_slice() {
+                [Value::Array(params), Value::Array(..)]
+                | [Value::Array(params), Value::Array(..), Value::String(..)]
+                | [Value::Array(params), Value::Array(..), Value::Null] => {
+                    params.iter().all(Value::is_quoted_identifier)
+                }
+                _ => false,
+            },
+            _ => false,
+        }
+    }
 }
 
 impl Hash for Value {
     fn hash<H: Hasher>(&self, state: &mut H) {
+        mem::discriminant(self).hash(state);
+
         match self {
-            Value::Null => Value::Null.hash(state),
+            Value::Boolean(v) => v.hash(state),
 
             Value::Int(v) => v.hash(state),
 
+            // `0.0` and `-0.0` are equal, so they must hash the same
+            Value::Float(v) => {
+                (if *v == 0.0 { 0.0f64 } else { *v }).to_bits().hash(state)
+            }
+
+            Value::String(v) | Value::Identifier(v) => v.hash(state),
+
+            Value::Char(v) => v.hash(state),
+
+            Value::Bytes(v) => v.hash(state),
+
+            Value::Quote(v) => v.hash(state),
+
             _ => (),
         }
     }
@@ -138,6 +254,7 @@ impl PartialEq for Value {
     fn eq(&self, other: &Value) -> bool {
         return match (self, other) {
             (Value::String(a), Value::String(b)) => a == b,
+            (Value::Char(a), Value::Char(b)) => a == b,
             (Value::Int(a), Value::Int(b)) => a == b,
             (Value::Float(a), Value::Float(b)) => a == b,
             (Value::Boolean(a), Value::Boolean(b)) => a == b,
@@ -148,8 +265,63 @@ impl PartialEq for Value {
                 a as *const _ == b as *const _
             }
             (Value::Array(a), Value::Array(b)) => a == b,
+            (Value::Bytes(a), Value::Bytes(b)) => a == b,
+            (Value::Composed(a), Value::Composed(b)) => a == b,
+            (
+                Value::Partial { func: fa, args: aa },
+                Value::Partial { func: fb, args: ab },
+            ) => fa == fb && aa == ab,
+            (
+                Value::Struct {
+                    name: na,
+                    fields: fa,
+                },
+                Value::Struct {
+                    name: nb,
+                    fields: fb,
+                },
+            ) => na == nb && fa == fb,
+            (
+                Value::StructType {
+                    name: na,
+                    fields: fa,
+                },
+                Value::StructType {
+                    name: nb,
+                    fields: fb,
+                },
+            ) => na == nb && fa == fb,
+
+            (
+                Value::Traced { name: na, func: fa },
+                Value::Traced { name: nb, func: fb },
+            ) => na == nb && fa == fb,
+
+            (
+                Value::Memoized { cache: ca, .. },
+                Value::Memoized { cache: cb, .. },
+            ) => ca == cb,
+
+            (
+                Value::Namespaced {
+                    namespace: na,
+                    func: fa,
+                },
+                Value::Namespaced {
+                    namespace: nb,
+                    func: fb,
+                },
+            ) => na == nb && fa == fb,
 
             (Value::Dict(a), Value::Dict(b)) => a == b,
+
+            // Quoted code holds lexical tokens
+            (Value::OpenFunc, Value::OpenFunc)
+            | (Value::CloseFunc, Value::CloseFunc)
+            | (Value::OpenBrace, Value::OpenBrace)
+            | (Value::CloseBrace, Value::CloseBrace)
+            | (Value::BackTick, Value::BackTick)
+            | (Value::Comma, Value::Comma) => true,
             // {
             // if a.len() != b.len() { return false; }
             //
@@ -177,7 +349,9 @@ impl fmt::Debug for Value {
             Value::Int(i) => write!(fm, "Int"),
             Value::Float(fl) => write!(fm, "Float"),
             Value::String(s) => write!(fm, "String"),
+            Value::Char(c) => write!(fm, "Char"),
             Value::Array(a) => write!(fm, "Array"),
+            Value::Bytes(b) => write!(fm, "Bytes"),
             Value::OpenFunc => write!(fm, "("),
             Value::CloseFunc => write!(fm, ")"),
             Value::OpenBrace => write!(fm, "["),
@@ -187,38 +361,172 @@ impl fmt::Debug for Value {
             Value::Comma => write!(fm, ","),
             Value::Func { f } => write!(fm, "Func"),
             Value::Dict(h) => write!(fm, "Dict"),
+            Value::Composed(c) => write!(fm, "Func"),
+            Value::Partial { func, args } => write!(fm, "Func"),
 
             Value::Struct { name, fields } => write!(fm, "Struct"),
+            Value::StructType { name, fields } => write!(fm, "StructType"),
+            Value::Traced { name, func } => write!(fm, "Func"),
+            Value::Memoized { func, cache } => write!(fm, "Func"),
+            Value::Namespaced { namespace, func } => write!(fm, "Func"),
         }
     }
 }
 
+/// Something an Environment can print to. Every `Write` type qualifies; the
+/// `Any` bound lets `Environment::into_output` hand the sink back.
+pub trait OutputSink: Write + Any {
+    fn into_any(self: Box<Self>) -> Box<dyn Any>;
+}
+
+impl<W: Write + Any> OutputSink for W {
+    fn into_any(self: Box<Self>) -> Box<dyn Any> {
+        self
+    }
+}
+
 pub struct Environment {
     scopes: Vec<SymTab>,
-    return_vals: Vec<Value>,
+    /// Where the printing builtins write to.
+    output: Box<dyn OutputSink>,
+    /// Buffers for nested `with-output-to-string` calls. The innermost one
+    /// receives output instead of `output`.
+    captures: Vec<Vec<u8>>,
+    /// Test bodies registered with `deftest`, in definition order.
+    tests: Vec<(String, Value)>,
+    /// Result caches for `memoize`d functions by cache id, each keyed by
+    /// argument list.
+    memos: HashMap<usize, HashMap<Vec<Value>, Value>>,
+    /// Names marked by `freeze` in each scope, parallel to `scopes`.
+    frozen: Vec<HashSet<String>>,
+    /// How many LambdaCore function calls are currently running.
+    depth: usize,
+    /// How deep `depth` may go before calls fail, see `set-recursion-limit`.
+    max_depth: usize,
+    /// The bindings of each module imported under an alias, by alias.
+    namespaces: HashMap<String, SymTab>,
 }
 
+/// The default for `Environment::max_depth`. The interpreter thread's stack
+/// in `main` is sized to fit this many nested calls.
+pub const LCORE_RECURSION_LIMIT: usize = 10000;
+
 impl Environment {
+    /// An empty environment that prints to stdout.
     pub fn new() -> Environment {
+        Environment::with_output(Box::new(io::stdout()))
+    }
+
+    /// An empty environment that prints to `output`, e.g. a file or a
+    /// `Vec<u8>`.
+    pub fn with_output(output: Box<dyn OutputSink>) -> Environment {
         Environment {
             scopes: Vec::new(),
-            return_vals: vec![Value::Null],
+            output,
+            captures: Vec::new(),
+            tests: Vec::new(),
+            memos: HashMap::new(),
+            frozen: Vec::new(),
+            depth: 0,
+            max_depth: LCORE_RECURSION_LIMIT,
+            namespaces: HashMap::new(),
+        }
+    }
+
+    /// Give back the sink passed to `with_output`, or None if it isn't a `W`.
+    pub fn into_output<W: Write + Any>(self) -> Option<W> {
+        self.output.into_any().downcast().ok().map(|output| *output)
+    }
+
+    /// The writer printing builtins should use right now.
+    pub fn output(&mut self) -> &mut dyn Write {
+        match self.captures.last_mut() {
+            Some(buffer) => buffer,
+            None => &mut *self.output,
+        }
+    }
+
+    /// Send all further output to a fresh buffer until `end_capture`.
+    pub fn begin_capture(&mut self) {
+        self.captures.push(Vec::new());
+    }
+
+    /// Stop the innermost capture and return what was written to it.
+    pub fn end_capture(&mut self) -> Vec<u8> {
+        self.captures.pop().unwrap_or_default()
+    }
+
+    /// Register a test body, replacing any earlier test with the same name.
+    pub fn add_test(&mut self, name: String, body: Value) {
+        match self.tests.iter_mut().find(|(n, _)| *n == name) {
+            Some(test) => test.1 = body,
+            None => self.tests.push((name, body)),
+        }
+    }
+
+    pub fn tests(&self) -> Vec<(String, Value)> {
+        self.tests.clone()
+    }
+
+    pub fn memo_get(&self, memo: usize, args: &[Value]) -> Option<&Value> {
+        self.memos.get(&memo).and_then(|cache| cache.get(args))
+    }
+
+    pub fn memo_insert(
+        &mut self,
+        memo: usize,
+        args: Vec<Value>,
+        result: Value,
+    ) {
+        self.memos.entry(memo).or_default().insert(args, result);
+    }
+
+    /// Note that a LambdaCore function is being entered, failing instead if
+    /// that would nest calls deeper than the recursion limit.
+    pub fn enter_call(&mut self) -> Result<(), LCoreError> {
+        if self.depth >= self.max_depth {
+            return Err(LCoreError::LambdaCoreError(format!(
+                "LambdaCoreError: recursion limit exceeded ({})",
+                self.max_depth
+            )));
         }
+        self.depth += 1;
+        Ok(())
+    }
+
+    pub fn exit_call(&mut self) {
+        self.depth -= 1;
+    }
+
+    pub fn set_recursion_limit(&mut self, limit: usize) {
+        self.max_depth = limit;
+    }
+
+    /// Make a module's bindings available as `alias/name`, replacing any
+    /// module previously imported under the same alias.
+    pub fn add_namespace(&mut self, alias: String, bindings: SymTab) {
+        self.namespaces.insert(alias, bindings);
     }
 
-    pub fn current_ret_index(&self) -> usize {
-        self.return_vals.len() - 1
+    pub fn namespace_get(&self, alias: &str, name: &str) -> Option<&Value> {
+        self.namespaces
+            .get(alias)
+            .and_then(|bindings| bindings.get(name))
     }
 
-    pub fn pop_ret_index(&mut self, index: usize) -> Value {
-        // Remove everything after the index
-        self.return_vals.truncate(index + 1);
-        // return the item at the index
-        self.return_vals.pop().unwrap()
+    /// Push a module's bindings as the innermost scope so that its functions
+    /// can call each other by their unqualified names.
+    pub fn enter_namespace(&mut self, alias: &str) {
+        let bindings = self.namespaces.get(alias).cloned().unwrap_or_default();
+        self.scopes.push(bindings);
+        self.frozen.push(HashSet::new());
     }
 
-    pub fn push_ret(&mut self, value: Value) {
-        self.return_vals.push(value);
+    /// Pop the scope pushed by `enter_namespace`, keeping any changes the
+    /// module's functions made to its bindings.
+    pub fn exit_namespace(&mut self, alias: &str) {
+        let bindings = self.pop();
+        self.namespaces.insert(alias.to_string(), bindings);
     }
 
     // fn get_iter(&mut self) -> i32 {
@@ -236,12 +544,36 @@ impl Environment {
 
     pub fn push(&mut self) {
         self.scopes.push(SymTab::new());
+        self.frozen.push(HashSet::new());
     }
 
     pub fn pop(&mut self) -> SymTab {
+        self.frozen.pop();
         self.scopes.pop().unwrap()
     }
 
+    /// Mark the innermost binding of a name as frozen. Returns false if the
+    /// name isn't bound. The mark belongs to the binding, not the value: a
+    /// copy bound to another name isn't frozen. Builtins that rebind names
+    /// check `is_frozen` first, since `insert` itself doesn't.
+    pub fn freeze(&mut self, name: &str) -> bool {
+        match self.scopes.iter().rposition(|s| s.contains_key(name)) {
+            Some(i) => {
+                self.frozen[i].insert(name.to_string());
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Whether the innermost binding of a name has been frozen.
+    pub fn is_frozen(&self, name: &str) -> bool {
+        match self.scopes.iter().rposition(|s| s.contains_key(name)) {
+            Some(i) => self.frozen[i].contains(name),
+            None => false,
+        }
+    }
+
     pub fn insert(&mut self, key: String, value: Value) {
         // NOTE(pebaz): Need to check if the var exists first. If it exists in
         // another block, set that var's value, don't define a new one.
@@ -256,6 +588,21 @@ impl Environment {
         }
     }
 
+    /// Bind a name in the innermost scope, shadowing any outer binding rather
+    /// than overwriting it like `insert` does.
+    pub fn define(&mut self, key: String, value: Value) {
+        self.scopes.last_mut().unwrap().insert(key, value);
+    }
+
+    /// Bind a name in the outermost scope so that it outlives the current
+    /// call and is visible everywhere that doesn't shadow it.
+    pub fn global_insert(&mut self, key: String, value: Value) {
+        if self.scopes.is_empty() {
+            self.push();
+        }
+        self.scopes[0].insert(key, value);
+    }
+
     pub fn contains_key(&self, name: String) -> bool {
         for scope in self.scopes.iter().rev() {
             // println!("{:p}: ", scope);
@@ -266,6 +613,15 @@ impl Environment {
         false
     }
 
+    /// Every visible binding, with inner scopes shadowing outer ones.
+    pub fn bindings(&self) -> SymTab {
+        let mut merged = SymTab::new();
+        for scope in &self.scopes {
+            merged.extend(scope.iter().map(|(k, v)| (k.clone(), v.clone())));
+        }
+        merged
+    }
+
     pub fn get(&mut self, name: String) -> Option<&mut Value> {
         for scope in &mut self.scopes.iter_mut().rev() {
             if let Some(value) = scope.get_mut(&name) {
@@ -282,8 +638,8 @@ pub enum LCoreError {
     IndexError(String),
     ArgumentError(String),
     NameError(String),
-    // ReturnError(Value),
-    ReturnError,
+    /// Unwinds to the enclosing LambdaCore function with the value to return
+    ReturnError(Value),
     BreakError,
 }
 
@@ -304,31 +660,85 @@ impl LCoreError {
         Err(LCoreError::NameError(msg))
     }
 
-    // pub fn Return(val: Value) -> Result<Value, LCoreError> {
-    pub fn Return() -> Result<Value, LCoreError> {
-        // Err(LCoreError::ReturnError(val))
-        Err(LCoreError::ReturnError)
+    pub fn Return(val: Value) -> Result<Value, LCoreError> {
+        Err(LCoreError::ReturnError(val))
     }
 
     pub fn Break() -> Result<Value, LCoreError> {
         Err(LCoreError::BreakError)
     }
+
+    /// Convert a catchable error into a Dict with `type` (e.g. "IndexError")
+    /// and `message` keys. The `"IndexError: "` style prefix is stripped from
+    /// the message. Control flow (`return` and `break`) is not catchable.
+    pub fn to_value(&self) -> Option<Value> {
+        let (kind, msg) = match self {
+            LCoreError::LambdaCoreError(s) => ("LambdaCoreError", s),
+            LCoreError::IndexError(s) => ("IndexError", s),
+            LCoreError::ArgumentError(s) => ("ArgumentError", s),
+            LCoreError::NameError(s) => ("NameError", s),
+            LCoreError::ReturnError(..) | LCoreError::BreakError => {
+                return None
+            }
+        };
+
+        let prefix = format!("{}: ", kind);
+        let msg = msg.strip_prefix(&prefix).unwrap_or(msg);
+
+        let mut dict = HashMap::new();
+        dict.insert(
+            Value::String("type".to_string()),
+            Value::String(kind.to_string()),
+        );
+        dict.insert(
+            Value::String("message".to_string()),
+            Value::String(msg.to_string()),
+        );
+
+        Some(Value::Dict(dict))
+    }
+}
+
+/// The message shown for an error that reaches the top level, always
+/// prefixed with its kind (e.g. `"NameError: ..."`).
+pub fn format_error(err: &LCoreError) -> String {
+    let (kind, msg) = match err {
+        LCoreError::LambdaCoreError(s) => ("LambdaCoreError", s.as_str()),
+        LCoreError::IndexError(s) => ("IndexError", s.as_str()),
+        LCoreError::ArgumentError(s) => ("ArgumentError", s.as_str()),
+        LCoreError::NameError(s) => ("NameError", s.as_str()),
+        LCoreError::ReturnError(..) => {
+            ("ReturnError", "Cannot return from outside of a function")
+        }
+        LCoreError::BreakError => {
+            ("BreakError", "Cannot break from outside of a loop")
+        }
+    };
+
+    let prefix = format!("{}: ", kind);
+    if msg.starts_with(&prefix) {
+        msg.to_string()
+    } else {
+        prefix + msg
+    }
 }
 
 
 /// Turn tokens into intermediate code.
 ///
-/// Returns: The count of the lines of code in the file.
+/// Returns: The count of the lines of code in the file, or an ArgumentError
+/// for literals that can't be represented (out of range numbers and bad Dict
+/// keys).
 pub fn lcore_parse(
     node: Pair<'_, Rule>,
     stack: &mut VecDeque<Value>,
-) -> usize {
+) -> Result<usize, LCoreError> {
     let mut loc = 0;
 
     match node.as_rule() {
         Rule::Program => {
             for rule in node.into_inner() {
-                loc += lcore_parse(rule, stack);
+                loc += lcore_parse(rule, stack)?;
             }
         }
 
@@ -346,7 +756,7 @@ pub fn lcore_parse(
             };
 
             for rule in rules {
-                loc += lcore_parse(rule, stack);
+                loc += lcore_parse(rule, stack)?;
             }
             stack.push_back(Value::CloseFunc);
         }
@@ -358,7 +768,7 @@ pub fn lcore_parse(
 
             for rule in node.into_inner() {
                 // loc += lcore_parse(rule, stack);
-                loc += lcore_parse(rule, &mut array_stack);
+                loc += lcore_parse(rule, &mut array_stack)?;
             }
 
             let mut new_array = Vec::new();
@@ -369,15 +779,46 @@ pub fn lcore_parse(
             // stack.push_back(Value::CloseBrace);
         }
 
+        Rule::Dict => {
+            let mut dict = HashMap::new();
+
+            for entry in node.into_inner() {
+                let mut items = VecDeque::new();
+                for rule in entry.into_inner() {
+                    loc += lcore_parse(rule, &mut items)?;
+                }
+
+                let key = items.pop_front().unwrap();
+                let value = items.pop_front().unwrap();
+
+                lcore_check_key(&key)?;
+                if dict.contains_key(&key) {
+                    return Err(LCoreError::ArgumentError(format!(
+                        "ArgumentError: Duplicate key {} in Dict literal",
+                        lcore_value_to_string(&key, true)
+                    )));
+                }
+                dict.insert(key, value);
+            }
+
+            stack.push_back(Value::Dict(dict));
+        }
+
         Rule::Number => {
-            if node.as_str().contains(".") {
-                stack.push_back(Value::Float(
-                    FromStr::from_str(node.as_str()).unwrap(),
-                ))
+            let number = if node.as_str().contains(['.', 'e', 'E']) {
+                f64::from_str(node.as_str()).ok().map(Value::Float)
             } else {
-                stack.push_back(Value::Int(
-                    FromStr::from_str(node.as_str()).unwrap(),
-                ))
+                i64::from_str(node.as_str()).ok().map(Value::Int)
+            };
+
+            match number {
+                Some(number) => stack.push_back(number),
+                None => {
+                    return Err(LCoreError::ArgumentError(format!(
+                        "ArgumentError: Number {} is out of range",
+                        node.as_str()
+                    )))
+                }
             }
         }
 
@@ -390,14 +831,20 @@ pub fn lcore_parse(
             // QUOTES :/
 
             for rule in node.into_inner() {
-                loc += lcore_parse(rule, &mut quote_stack);
+                loc += lcore_parse(rule, &mut quote_stack)?;
             }
 
-            assert!(quote_stack.len() == 1);
-
-            stack.push_back(Value::Quote(Box::new(
-                quote_stack.pop_back().unwrap(),
-            )));
+            // A quoted function call like `'(f x)` is made up of several
+            // tokens, so keep them together like a quoted Array.
+            if quote_stack.len() == 1 {
+                stack.push_back(Value::Quote(Box::new(
+                    quote_stack.pop_back().unwrap(),
+                )));
+            } else {
+                stack.push_back(Value::Quote(Box::new(Value::Array(
+                    Vec::from_iter(quote_stack),
+                ))));
+            }
 
             // let mut new_array = Vec::new();
             // new_array.extend(quote_stack);
@@ -410,6 +857,16 @@ pub fn lcore_parse(
             stack.push_back(Value::String(string))
         }
 
+        Rule::Char => {
+            let c = match &node.as_str()[2..] {
+                "space" => ' ',
+                "newline" => '\n',
+                "tab" => '\t',
+                c => c.chars().next().unwrap(),
+            };
+            stack.push_back(Value::Char(c))
+        }
+
         Rule::BackTick => stack.push_back(Value::BackTick),
         Rule::Comma => stack.push_back(Value::Comma),
         Rule::Identifier => {
@@ -424,7 +881,7 @@ pub fn lcore_parse(
         _ => (),
     }
 
-    return loc;
+    Ok(loc)
 }
 
 /// Interpret a LambdaCore Program.
@@ -474,19 +931,20 @@ pub fn lcore_interpret_expression(
             if let Value::Array(ref mut v) = arrays[length - 1] {
                 // Lookup the current node and push it
                 let key = node.as_identifier();
-                if !symbol_table.contains_key(key.as_str().to_string()) {
-                    return Err(LCoreError::NameError(format!(
-                        "NameError: Cannot lookup name: \"{}\"",
-                        key
-                    )));
-                }
+                let value = match symbol_table.get(key.to_string()) {
+                    Some(value) => Some(value.clone()),
+                    None => lcore_qualified_get(key, symbol_table),
+                };
 
-                v.push(
-                    symbol_table
-                        .get(key.as_str().to_string())
-                        .unwrap()
-                        .clone(),
-                )
+                match value {
+                    Some(value) => v.push(value),
+                    None => {
+                        return Err(LCoreError::NameError(format!(
+                            "NameError: Cannot lookup name: \"{}\"",
+                            key
+                        )))
+                    }
+                }
             }
         }
 
@@ -502,77 +960,7 @@ pub fn lcore_interpret_expression(
                 let func = v.remove(0);
                 let mut args = arrays.pop().unwrap();
 
-                // IMPORTANT(pebaz): Either the func is a native function
-                // or a LambdaCore function.
-
-                let ret = match func {
-                    Value::Func { f } => f(&mut args, symbol_table),
-
-                    Value::Array(a) => {
-                        let arg_names = match &a[0] {
-                            Value::Array(argument_names) => argument_names,
-                            _ => unreachable!(),
-                        };
-
-                        // TODO(pebaz): In order to do Tail-Call
-                        // Optimization,
-                        // it is necessary to remove the next code line.
-                        // This will allow the function to reuse names
-                        // (and therefore storage) from previous call.
-
-                        // Push a new scope
-                        symbol_table.push();
-
-                        // Bind all arguments to the given values
-                        if let Value::Array(ref mut v) = args {
-                            let mut count = v.len();
-                            while let Some(value) = v.pop() {
-                                count -= 1; // Iterate in reverse
-                                match &arg_names[count] {
-                                    Value::Quote(v) => {
-                                        symbol_table.insert(
-                                            v.as_identifier().to_string(),
-                                            value,
-                                        );
-                                    }
-
-                                    _ => unreachable!(),
-                                }
-                            }
-                        }
-
-                        let ret = match &a[1] {
-                            Value::Array(def) => {
-                                let mut body =
-                                    VecDeque::from_iter(def.clone());
-
-                                // lcore_interpret(&mut body, symbol_table)
-                                let return_point =
-                                    symbol_table.current_ret_index();
-                                let return_this =
-                                    lcore_interpret(&mut body, symbol_table);
-                                if symbol_table.current_ret_index()
-                                    > return_point
-                                {
-                                    let r = symbol_table
-                                        .pop_ret_index(return_point + 1);
-                                    Ok(r)
-                                } else {
-                                    return_this
-                                }
-                            }
-                            _ => unreachable!(),
-                        };
-
-                        // Reclaim all old variables
-                        symbol_table.pop();
-
-                        // Value::Null
-                        ret
-                    }
-
-                    _ => Ok(Value::Null),
-                };
+                let ret = lcore_call(&func, &mut args, symbol_table);
 
                 let length = arrays.len();
                 if let Value::Array(ref mut v) = arrays[length - 1] {
@@ -604,9 +992,7 @@ pub fn lcore_interpret_expression(
 
                 if let Err(ref err) = result {
                     match err {
-                        // LCoreError::ReturnError(v) => return
-                        // Err(err.clone()),
-                        LCoreError::ReturnError => return Err(err.clone()),
+                        LCoreError::ReturnError(..) => return Err(err.clone()),
                         LCoreError::BreakError => return Err(err.clone()),
 
                         _ => return Err(err.clone()),
@@ -633,6 +1019,191 @@ pub fn lcore_interpret_expression(
     Ok(Value::Null)
 }
 
+/// Resolve a qualified name `alias/name` against the module imported as
+/// `alias`. Functions from the module are wrapped so that they run in the
+/// module's own namespace.
+fn lcore_qualified_get(
+    key: &str,
+    symbol_table: &Environment,
+) -> Option<Value> {
+    let (alias, name) = key.split_once('/')?;
+
+    match symbol_table.namespace_get(alias, name)? {
+        value @ Value::Func { .. } => Some(value.clone()),
+        value if value.is_callable() => Some(Value::Namespaced {
+            namespace: alias.to_string(),
+            func: Box::new(value.clone()),
+        }),
+        value => Some(value.clone()),
+    }
+}
+
+/// Call a native function, a LambdaCore function, a composition, or a
+/// partial application with an Array of already-evaluated arguments.
+pub fn lcore_call(
+    func: &Value,
+    args: &mut Value,
+    symbol_table: &mut Environment,
+) -> Result<Value, LCoreError> {
+    // IMPORTANT(pebaz): Either the func is a native function
+    // or a LambdaCore function.
+
+    match func {
+        Value::Func { f } => f(args, symbol_table),
+
+        Value::Array(a) if func.is_callable() => {
+            let arg_names = match &a[0] {
+                Value::Array(argument_names) => argument_names,
+                _ => unreachable!(),
+            };
+
+            // TODO(pebaz): In order to do Tail-Call
+            // Optimization,
+            // it is necessary to remove the next code line.
+            // This will allow the function to reuse names
+            // (and therefore storage) from previous call.
+
+            symbol_table.enter_call()?;
+
+            // Push a new scope
+            symbol_table.push();
+
+            // Bind all arguments to the given values
+            if let Value::Array(ref mut v) = args {
+                let mut count = v.len();
+                while let Some(value) = v.pop() {
+                    count -= 1; // Iterate in reverse
+
+                    // Parameters shadow outer names so that recursive calls
+                    // don't overwrite their caller's arguments
+                    match &arg_names[count] {
+                        Value::Quote(v) => {
+                            symbol_table
+                                .define(v.as_identifier().to_string(), value);
+                        }
+
+                        _ => unreachable!(),
+                    }
+                }
+            }
+
+            let ret = match &a[1] {
+                Value::Array(def) => {
+                    let mut body = VecDeque::from_iter(def.clone());
+
+                    match lcore_interpret(&mut body, symbol_table) {
+                        // `return` unwinds to here with the value to return
+                        Err(LCoreError::ReturnError(value)) => Ok(value),
+                        other => other,
+                    }
+                }
+                _ => unreachable!(),
+            };
+
+            // Reclaim all old variables
+            symbol_table.pop();
+            symbol_table.exit_call();
+
+            // Value::Null
+            ret
+        }
+
+        Value::Composed(funcs) => {
+            // Compositions are applied right-to-left
+            let mut funcs = funcs.iter().rev();
+            let mut result = match funcs.next() {
+                Some(f) => lcore_call(f, args, symbol_table)?,
+                None => return Ok(Value::Null),
+            };
+
+            for f in funcs {
+                result = lcore_call(
+                    f,
+                    &mut Value::Array(vec![result]),
+                    symbol_table,
+                )?;
+            }
+
+            Ok(result)
+        }
+
+        Value::Partial {
+            func,
+            args: captured,
+        } => {
+            let mut all_args = captured.clone();
+            if let Value::Array(v) = args {
+                all_args.append(v);
+            }
+
+            lcore_call(func, &mut Value::Array(all_args), symbol_table)
+        }
+
+        Value::StructType { name, fields } => {
+            let values = args.as_array();
+
+            if values.len() != fields.len() {
+                return Err(LCoreError::ArgumentError(format!(
+                    "ArgumentError: \"{}\" takes {} fields, got {}",
+                    name,
+                    fields.len(),
+                    values.len()
+                )));
+            }
+
+            Ok(Value::Struct {
+                name: name.clone(),
+                fields: fields.iter().cloned().zip(values.clone()).collect(),
+            })
+        }
+
+        Value::Traced { name, func } => {
+            let mut stderr = io::stderr();
+
+            write!(stderr, "TRACE: ({}", name).ok();
+            for arg in args.as_array() {
+                write!(stderr, " ").ok();
+                lcore_write_value(&mut stderr, arg, true).ok();
+            }
+            writeln!(stderr, ")").ok();
+
+            let result = lcore_call(func, args, symbol_table)?;
+
+            write!(stderr, "TRACE: {} => ", name).ok();
+            lcore_write_value(&mut stderr, &result, true).ok();
+            writeln!(stderr).ok();
+
+            Ok(result)
+        }
+
+        Value::Namespaced { namespace, func } => {
+            symbol_table.enter_namespace(namespace);
+            let result = lcore_call(func, args, symbol_table);
+            symbol_table.exit_namespace(namespace);
+            result
+        }
+
+        Value::Memoized { func, cache } => {
+            // Calls with unhashable arguments can't be cached, so they always
+            // run the function.
+            let key = args.as_array().clone();
+            if !key.iter().all(Value::is_hashable) {
+                return lcore_call(func, args, symbol_table);
+            }
+
+            if let Some(result) = symbol_table.memo_get(*cache, &key) {
+                return Ok(result.clone());
+            }
+
+            let result = lcore_call(func, args, symbol_table)?;
+            symbol_table.memo_insert(*cache, key, result.clone());
+            Ok(result)
+        }
+
+        _ => Ok(Value::Null),
+    }
+}
+
 pub fn lcore_interpret_array(
     element: &Value,
     symbol_table: &mut Environment,
@@ -661,6 +1232,63 @@ pub fn lcore_interpret_array(
     }
 }
 
+/// Read one value from its source text without evaluating it, so that
+/// `(read (repr v))` gives back `v`. A function call like `(f x)` reads as
+/// the Array of its tokens, the same as the body of a quoted call.
+pub fn lcore_read_value(source: &str) -> Result<Value, LCoreError> {
+    let datum = match LambdaCoreParser::parse(Rule::Datum, source) {
+        Ok(mut pairs) => pairs.next().unwrap(),
+        Err(e) => {
+            return Err(LCoreError::ArgumentError(format!(
+                "ArgumentError: Failed to read: {}",
+                e
+            )))
+        }
+    };
+
+    let mut stack = VecDeque::new();
+    for rule in datum.into_inner() {
+        lcore_parse(rule, &mut stack)?;
+    }
+
+    if stack.len() == 1 {
+        Ok(stack.pop_back().unwrap())
+    } else {
+        Ok(Value::Array(Vec::from_iter(stack)))
+    }
+}
+
+/// Count the lines of code in some source, the same way `lcore_parse` does:
+/// one per newline ending a top-level line. Newlines inside a form or block
+/// comment aren't counted, so a form that spans several lines counts once.
+pub fn lcore_loc_of(source: &str) -> Result<usize, LCoreError> {
+    let program = match LambdaCoreParser::parse(Rule::Program, source) {
+        Ok(mut pairs) => pairs.next().unwrap(),
+        Err(e) => {
+            return Err(LCoreError::LambdaCoreError(format!(
+                "LambdaCoreError: Failed to parse: {}",
+                e
+            )))
+        }
+    };
+
+    lcore_parse(program, &mut VecDeque::new())
+}
+
+/// Check that some source parses as a Program without running any of it.
+/// Returns the parser's message if it doesn't, including literals that
+/// `lcore_parse` rejects.
+pub fn lcore_parse_error(source: &str) -> Option<String> {
+    match LambdaCoreParser::parse(Rule::Program, source) {
+        Ok(mut pairs) => {
+            lcore_parse(pairs.next().unwrap(), &mut VecDeque::new())
+                .err()
+                .map(|e| format_error(&e))
+        }
+        Err(e) => Some(e.to_string()),
+    }
+}
+
 pub fn count_newlines(s: &str) -> usize {
     s.as_bytes().iter().filter(|&&c| c == b'\n').count()
 }
@@ -693,29 +1321,15 @@ pub fn lcore_repl() {
             match LambdaCoreParser::parse(Rule::Program, &input) {
                 Ok(mut i) => {
                     let mut stack = VecDeque::new();
-                    lcore_parse(i.next().unwrap(), &mut stack);
+                    let parsed = lcore_parse(i.next().unwrap(), &mut stack);
 
                     // lcore_interpret(&mut stack, &mut symbol_table);
 
-                    match lcore_interpret(&mut stack, &mut symbol_table) {
+                    match parsed.and_then(|_| {
+                        lcore_interpret(&mut stack, &mut symbol_table)
+                    }) {
                         // NOTE(pebaz): Make sure errors are not lost
-                        Err(err) => match err {
-                            LCoreError::LambdaCoreError(s) => {
-                                println!("{}", s)
-                            }
-                            LCoreError::IndexError(s) => println!("{}", s),
-                            LCoreError::ArgumentError(s) => println!("{}", s),
-                            LCoreError::NameError(s) => println!("{}", s),
-
-                            // LCoreError::ReturnError(v) => println!("NOT
-                            // IMPLEMENTED ERROR"),
-                            LCoreError::ReturnError => {
-                                println!("NOT IMPLEMENTED ERROR")
-                            }
-                            LCoreError::BreakError => {
-                                println!("NOT IMPLEMENTED!")
-                            }
-                        },
+                        Err(err) => println!("{}", format_error(&err)),
 
                         // NOTE(pebaz): Repr print a non-null value
                         Ok(val) => {
@@ -791,20 +1405,10 @@ pub fn lcore_import_file(file: String) -> SymTab {
     let planned = stack.capacity();
     let loc = lcore_parse(program, &mut stack);
 
-    if let Err(err) = lcore_interpret(&mut stack, &mut symbol_table) {
-        match err {
-            LCoreError::LambdaCoreError(s) => println!("{}", s),
-            LCoreError::IndexError(s) => println!("{}", s),
-            LCoreError::ArgumentError(s) => println!("{}", s),
-            LCoreError::NameError(s) => println!("{}", s),
-
-            // LCoreError::ReturnError(v) => println!("IMPORT: NOT IMPLEMENTED
-            // ERROR"),
-            LCoreError::ReturnError => {
-                println!("IMPORT: NOT IMPLEMENTED ERROR")
-            }
-            LCoreError::BreakError => println!("IMPORT: NOT IMPLEMENTED!"),
-        }
+    if let Err(err) =
+        loc.and_then(|_| lcore_interpret(&mut stack, &mut symbol_table))
+    {
+        println!("{}", format_error(&err));
     }
 
     // Return the resulting namespace to be merged with importing module
@@ -812,6 +1416,41 @@ pub fn lcore_import_file(file: String) -> SymTab {
     symbol_table.pop()
 }
 
+thread_local! {
+    /// Bindings exported by each module that has already been imported, keyed
+    /// by canonical path. `None` marks a module that is still being evaluated.
+    static LCORE_MODULES: RefCell<HashMap<PathBuf, Option<SymTab>>> =
+        RefCell::new(HashMap::new());
+}
+
+/// Import a module, only evaluating its top-level code the first time. Later
+/// imports re-expose the bindings from that first evaluation. Importing a
+/// module while it is still being evaluated (directly or through other
+/// modules) is an error.
+pub fn lcore_import_module(file: String) -> Result<SymTab, LCoreError> {
+    let key = fs::canonicalize(&file).unwrap_or_else(|_| PathBuf::from(&file));
+
+    match LCORE_MODULES.with(|modules| modules.borrow().get(&key).cloned()) {
+        Some(Some(bindings)) => return Ok(bindings),
+        Some(None) => {
+            return Err(LCoreError::LambdaCoreError(format!(
+                "LambdaCoreError: circular import of \"{}\"",
+                file
+            )))
+        }
+        None => (),
+    }
+
+    LCORE_MODULES
+        .with(|modules| modules.borrow_mut().insert(key.clone(), None));
+    let bindings = lcore_import_file(file);
+    LCORE_MODULES.with(|modules| {
+        modules.borrow_mut().insert(key, Some(bindings.clone()))
+    });
+
+    Ok(bindings)
+}
+
 pub fn lcore_execute_string(code: String) {
     let program = LambdaCoreParser::parse(Rule::Program, &code)
         .expect("LCORE: Failed To Parse")
@@ -825,24 +1464,54 @@ pub fn lcore_execute_string(code: String) {
 
     let mut stack = VecDeque::new();
     let planned = stack.capacity();
-    lcore_parse(program, &mut stack);
-
-    if let Err(err) = lcore_interpret(&mut stack, &mut symbol_table) {
-        match err {
-            LCoreError::LambdaCoreError(s) => println!("{}", s),
-            LCoreError::IndexError(s) => println!("{}", s),
-            LCoreError::ArgumentError(s) => println!("{}", s),
-            LCoreError::NameError(s) => println!("{}", s),
-
-            // LCoreError::ReturnError(v) => println!("EXECUTE_STRING: NOT
-            // IMPLEMENTED ERROR"),
-            LCoreError::ReturnError => {
-                println!("EXECUTE_STRING: NOT IMPLEMENTED ERROR")
-            }
-            LCoreError::BreakError => {
-                println!("EXECUTE_STRING: NOT IMPLEMENTED!")
-            }
-        }
+
+    if let Err(err) = lcore_parse(program, &mut stack)
+        .and_then(|_| lcore_interpret(&mut stack, &mut symbol_table))
+    {
+        println!("{}", format_error(&err));
     }
     symbol_table.pop();
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_output_sink() {
+        let program = LambdaCoreParser::parse(Rule::Program, "(print 42)")
+            .unwrap()
+            .next()
+            .unwrap();
+
+        let mut symbol_table = Environment::with_output(Box::new(Vec::new()));
+        symbol_table.push();
+        import_builtins(&mut symbol_table);
+
+        let mut stack = VecDeque::new();
+        lcore_parse(program, &mut stack).unwrap();
+        lcore_interpret(&mut stack, &mut symbol_table).unwrap();
+
+        let output: Vec<u8> = symbol_table.into_output().unwrap();
+        assert_eq!(output, b"42\n");
+    }
+
+    #[test]
+    fn test_loc_of() {
+        // Blank and comment-only lines count, a form or block comment
+        // spanning several lines counts once, and a last line without a
+        // newline doesn't count
+        let source = "(print 1)\n\
+                      \n\
+                      :: comment\n\
+                      \n\
+                      (print\n    2)\n\
+                      :> block\n   comment <:\n\
+                      (print 3)";
+        assert_eq!(lcore_loc_of(source).unwrap(), 6);
+
+        assert_eq!(lcore_loc_of("").unwrap(), 0);
+        assert_eq!(lcore_loc_of("\n\n").unwrap(), 2);
+        assert!(lcore_loc_of("(print 1").is_err());
+    }
+}
@@ -1,15 +1,38 @@
 #![cfg_attr(debug_assertions, allow(unused_imports))]
 #![cfg_attr(debug_assertions, allow(dead_code))]
 #![cfg_attr(debug_assertions, allow(unused_variables))]
-
 #![allow(non_snake_case)]
-
+// The interpreter's existing code leans on these patterns throughout (e.g.
+// `format!` for constant error messages, explicit `return` at the end of a
+// function, `&Box<Value>` parameters), and new builtins follow the same
+// idioms, so they are allowed crate-wide rather than rewritten piecemeal.
+#![allow(
+    clippy::borrow_deref_ref,
+    clippy::borrowed_box,
+    clippy::enum_variant_names,
+    clippy::expect_fun_call,
+    clippy::explicit_auto_deref,
+    clippy::let_unit_value,
+    clippy::manual_is_multiple_of,
+    clippy::needless_borrow,
+    clippy::needless_range_loop,
+    clippy::needless_return,
+    clippy::only_used_in_recursion,
+    clippy::println_empty_string,
+    clippy::ptr_eq,
+    clippy::question_mark,
+    clippy::single_char_add_str,
+    clippy::single_match,
+    clippy::unused_io_amount,
+    clippy::useless_format
+)]
 
 #[macro_use]
 extern crate clap;
 
 mod builtin;
 mod lcore;
+mod regex;
 
 use crate::builtin::*;
 use crate::lcore::pest::Parser;
@@ -22,7 +45,11 @@ use std::env;
 use std::fmt;
 use std::fs;
 use std::iter::FromIterator;
+use std::process;
 use std::str::FromStr;
+use std::thread;
+
+const LCORE_STACK_SIZE: usize = 1 << 30;
 
 fn main() {
     let matches = App::new("LambdaCore")
@@ -49,15 +76,32 @@ fn main() {
 
     // Get other CLI switches (not FILE yet)
 
-    let code_str = matches.value_of("code");
-    let code_file = matches.value_of("file");
+    let code_str = matches.value_of("code").map(String::from);
+    let code_file = matches.value_of("file").map(String::from);
 
-    match (code_file, code_str) {
-        (None, None) => lcore_repl(),
-        (None, Some(code)) => lcore_execute_string(code.to_string()),
-        (Some(file), None) => {
-            let _ = lcore_import_file(file.to_string());
+    // Every LambdaCore call nests several Rust calls, so the interpreter runs
+    // on a thread with room for `LCORE_RECURSION_LIMIT` of them.
+    let spawned =
+        thread::Builder::new()
+            .stack_size(LCORE_STACK_SIZE)
+            .spawn(move || match (code_file, code_str) {
+                (None, None) => lcore_repl(),
+                (None, Some(code)) => lcore_execute_string(code),
+                (Some(file), None) => {
+                    let _ = lcore_import_file(file);
+                }
+                _ => (),
+            });
+
+    let interpreter = match spawned {
+        Ok(interpreter) => interpreter,
+        Err(e) => {
+            eprintln!("LCORE: Failed to start the interpreter thread: {}", e);
+            process::exit(1);
         }
-        _ => (),
+    };
+
+    if interpreter.join().is_err() {
+        process::exit(101);
     }
 }
@@ -0,0 +1,52 @@
+/// A thin wrapper around the `regex` crate that gives the pattern builtins
+/// the operations they need and plain-text syntax errors.
+pub struct Regex {
+    re: ::regex::Regex,
+}
+
+impl Regex {
+    pub fn new(pattern: &str) -> Result<Regex, String> {
+        // Syntax errors span several lines pointing into the pattern, the
+        // last of which says what went wrong
+        match ::regex::Regex::new(pattern) {
+            Ok(re) => Ok(Regex { re }),
+            Err(e) => Err(e
+                .to_string()
+                .lines()
+                .last()
+                .unwrap_or_default()
+                .trim_start_matches("error: ")
+                .to_string()),
+        }
+    }
+
+    /// Compiles a pattern that only matches the whole of a text.
+    pub fn new_whole(pattern: &str) -> Result<Regex, String> {
+        // The pattern must stand on its own before it is anchored, or an
+        // unbalanced one like `a)|(?:b` would close the wrapping group
+        Regex::new(pattern)?;
+        Regex::new(&format!("\\A(?:{})\\z", pattern))
+    }
+
+    pub fn is_match(&self, text: &str) -> bool {
+        self.re.is_match(text)
+    }
+
+    /// Returns the first match in `text`: the whole match followed by each
+    /// capture group, with `None` for groups that did not participate.
+    pub fn captures(&self, text: &str) -> Option<Vec<Option<String>>> {
+        self.re.captures(text).map(|groups| {
+            groups
+                .iter()
+                .map(|g| g.map(|g| g.as_str().to_string()))
+                .collect()
+        })
+    }
+
+    /// Replaces every non-overlapping match in `text`. `$1` (or `${1}` when
+    /// followed by a letter or digit) in the replacement refers to a capture
+    /// group and `$$` is a literal `$`.
+    pub fn replace_all(&self, text: &str, replacement: &str) -> String {
+        self.re.replace_all(text, replacement).into_owned()
+    }
+}
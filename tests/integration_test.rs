@@ -1,3 +1,4 @@
+use std::path::Path;
 use std::process::Command;
 
 
@@ -17,6 +18,42 @@ fn run_file(file: String) -> String {
     String::from_utf8(output.stdout).unwrap()
 }
 
+fn run_code(code: &str) -> String {
+    let target = if cfg!(debug_assertions) {
+        "target/debug/lambda_core"
+    } else {
+        "target/release/lambda_core"
+    };
+
+    let output = Command::new(target).arg("-c").arg(code).output().unwrap();
+
+    String::from_utf8(output.stdout).unwrap()
+}
+
+fn run_code_stderr(code: &str) -> String {
+    let target = if cfg!(debug_assertions) {
+        "target/debug/lambda_core"
+    } else {
+        "target/release/lambda_core"
+    };
+
+    let output = Command::new(target).arg("-c").arg(code).output().unwrap();
+
+    String::from_utf8(output.stderr).unwrap()
+}
+
+/// Run `test` in a fresh, empty directory under the system's temp dir, and
+/// remove the directory once it passes.
+fn with_temp_dir(name: &str, test: impl FnOnce(&Path)) {
+    let dir = std::env::temp_dir().join(name);
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+
+    test(&dir);
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
 #[test]
 fn test_add() {
     let stdout = run_file("examples/add.lcore".to_string());
@@ -29,6 +66,126 @@ fn test_add() {
     assert_eq!(stdout, expect);
 }
 
+#[test]
+fn test_append_file() {
+    with_temp_dir("lcore_test_append_file", |dir| {
+        let log = dir.join("log.txt");
+
+        let code = format!(
+            "(print (append-file {0:?} \"first\n\")) \
+             (append-file {0:?} \"second\n\")",
+            log
+        );
+        assert_eq!(run_code(&code), "Null\n".to_string());
+        assert_eq!(
+            std::fs::read_to_string(&log).unwrap(),
+            "first\nsecond\n".to_string()
+        );
+
+        // A directory can't be opened for appending
+        assert!(run_code(&format!("(append-file {:?} \"x\")", dir))
+            .starts_with("LambdaCoreError: "));
+    });
+}
+
+#[test]
+fn test_approx_eq() {
+    let stdout = run_file("examples/approx-eq.lcore".to_string());
+    let expect = "False\n\
+                  True\n\
+                  False\n\
+                  True\n\
+                  7\n\
+                  1.5\n\
+                  \"abs-diff\" takes numbers, got String\n\
+                  9223372036854775807\n\
+                  \"abs-diff\" of 9223372036854775807 and -1 overflows an Int\n"
+        .to_string();
+    assert_eq!(stdout, expect);
+}
+
+#[test]
+fn test_arity() {
+    let stdout = run_file("examples/arity.lcore".to_string());
+    let expect = "\"+\" expected 2 arguments, got 1\n\
+                  \"=\" expected 2 arguments, got 1\n\
+                  \"get\" expected 2 arguments, got 1\n\
+                  \"not\" expected 1 arguments, got 0\n\
+                  \"defn\" expected 3 arguments, got 1\n\
+                  \"truthy?\" expected 1 arguments, got 0\n\
+                  \"repr\" expected 1 arguments, got 0\n\
+                  \"get-in\" expected 2 arguments, got 1\n\
+                  \"abs-diff\" expected 2 arguments, got 1\n\
+                  \"len\" expected 1 arguments, got 2\n\
+                  \"+\" expected 2 arguments, got 3\n\
+                  \"select\" expected 3 arguments, got 4\n\
+                  3\n"
+    .to_string();
+    assert_eq!(stdout, expect);
+}
+
+#[test]
+fn test_array_to_dict_indexed() {
+    let stdout = run_file("examples/array-to-dict-indexed.lcore".to_string());
+    let expect = "3\n\
+                  a\n\
+                  c\n\
+                  True\n\
+                  0\n\
+                  True\n\
+                  \"array->dict-indexed\" takes an Array\n"
+        .to_string();
+    assert_eq!(stdout, expect);
+}
+
+#[test]
+fn test_assert_eq() {
+    let stdout = run_file("examples/assert-eq.lcore".to_string());
+    let expect = "passed\n\
+                  Assertion failed: expected [1 3] but got [1 2]\n\
+                  Assertion failed: expected 1 but got \"1\"\n"
+        .to_string();
+    assert_eq!(stdout, expect);
+}
+
+#[test]
+fn test_base64() {
+    let stdout = run_file("examples/base64.lcore".to_string());
+    let expect = "TWFu\n\
+                  TWE=\n\
+                  TQ==\n\
+                  \n\
+                  aMOpbGxvIHfDtnJsZA==\n\
+                  Man\n\
+                  Ma\n\
+                  M\n\
+                  héllo wörld\n\
+                  Invalid base64 \"TQ=\"\n\
+                  Invalid base64 \"T!==\"\n\
+                  Invalid base64 \"TQ==TWFu\"\n\
+                  ArgumentError\n"
+        .to_string();
+    assert_eq!(stdout, expect);
+}
+
+#[test]
+fn test_bound() {
+    let stdout = run_file("examples/bound.lcore".to_string());
+    let expect = "True\n\
+                  True\n\
+                  False\n\
+                  True\n\
+                  quiet\n"
+        .to_string();
+    assert_eq!(stdout, expect);
+
+    assert_eq!(
+        run_code("(bound? 1)"),
+        "ArgumentError: \"bound?\" takes a quoted name or a String, got Int\n"
+            .to_string()
+    );
+}
+
 #[test]
 fn test_break() {
     let stdout = run_file("examples/break.lcore".to_string());
@@ -48,6 +205,147 @@ fn test_break() {
     assert_eq!(stdout, expect);
 }
 
+#[test]
+fn test_bytes() {
+    let file = std::env::temp_dir().join("lcore_test_bytes.bin");
+    std::fs::write(&file, [104, 105, 33]).unwrap();
+    let code = format!(
+        "(set 'b (read-bytes {:?}))
+         (print b)
+         (print (len b))
+         (print (get b 0))
+         (print (get b -1))
+         (print (bytes->string b))
+         (get b \"x\")",
+        file
+    );
+    assert_eq!(
+        run_code(&code),
+        "#bytes[104 105 33]\n3\n104\n33\nhi!\n\
+         ArgumentError: Cannot index Bytes with String\n"
+            .to_string()
+    );
+
+    std::fs::write(&file, [0xff, 0xfe]).unwrap();
+    let code = format!(
+        "(print (try '[(bytes->string (read-bytes {:?}))]
+                     'e '[(get e \"type\")]))",
+        file
+    );
+    assert_eq!(run_code(&code), "ArgumentError\n".to_string());
+    std::fs::remove_file(&file).unwrap();
+}
+
+#[test]
+fn test_case() {
+    let stdout = run_file("examples/case.lcore".to_string());
+    let expect = "It's Two!\n\
+                  2\n\
+                  Nothing matched :(\n\
+                  Null\n"
+        .to_string();
+    assert_eq!(stdout, expect);
+}
+
+#[test]
+fn test_char() {
+    let stdout = run_file("examples/char.lcore".to_string());
+    let expect = "a\n\
+                  [#\\a #\\space #\\newline]\n\
+                  True\n\
+                  False\n\
+                  True\n\
+                  97\n\
+                  B\n\
+                  True\n\
+                  122\n\
+                  955\n"
+        .to_string();
+    assert_eq!(stdout, expect);
+
+    assert_eq!(
+        run_code("(chr -1)"),
+        "ArgumentError: -1 is not a valid character code\n".to_string()
+    );
+}
+
+#[test]
+fn test_char_class() {
+    let stdout = run_file("examples/char-class.lcore".to_string());
+    let expect = "True\n\
+                  False\n\
+                  True\n\
+                  False\n\
+                  True\n\
+                  True\n\
+                  False\n\
+                  True\n\
+                  True\n\
+                  False\n\
+                  True\n\
+                  False\n\
+                  False\n\
+                  \"digit?\" takes a String or a Char\n"
+        .to_string();
+    assert_eq!(stdout, expect);
+}
+
+#[test]
+fn test_char_count() {
+    let stdout = run_file("examples/char-count.lcore".to_string());
+    let expect = "3\n\
+                  2\n\
+                  0\n\
+                  2\n\
+                  a_b_c\n\
+                  a-b-c\n\
+                  ωβω\n\
+                  \"char-count\" takes a String and a single character\n"
+        .to_string();
+    assert_eq!(stdout, expect);
+}
+
+#[test]
+fn test_chars() {
+    let stdout = run_file("examples/chars.lcore".to_string());
+    let expect = "[\"a\" \"b\" \"c\"]\n\
+                  3\n\
+                  abc\n\
+                  3\n\
+                  β\n\
+                  αβγ\n\
+                  []\n"
+        .to_string();
+    assert_eq!(stdout, expect);
+
+    assert_eq!(
+        run_code("(chars->string [\"a\" \"bc\"])"),
+        "ArgumentError: Expected a one-character String, got String\n"
+            .to_string()
+    );
+}
+
+#[test]
+fn test_chunk() {
+    let stdout = run_file("examples/chunk.lcore".to_string());
+    let expect = "[[1 2] [3 4]]\n\
+                  [[1 2] [3 4] [5]]\n\
+                  []\n\
+                  [[2 4 6] [1 3 5]]\n\
+                  [[] []]\n"
+        .to_string();
+    assert_eq!(stdout, expect);
+
+    assert_eq!(
+        run_code("(chunk [1 2] 0)"),
+        "ArgumentError: Chunk size must be positive (0)\n".to_string()
+    );
+    assert_eq!(
+        run_code("(partition len [[1] \"a\" 3])"),
+        "ArgumentError: Predicate returned non-boolean (Int)\n".to_string()
+    );
+}
+
 #[test]
 fn test_comment() {
     let stdout = run_file("examples/comment.lcore".to_string());
@@ -85,6 +383,133 @@ fn test_compare() {
     assert_eq!(stdout, expect);
 }
 
+#[test]
+fn test_compose() {
+    let stdout = run_file("examples/compose.lcore".to_string());
+    let expect = "11\n\
+                  12\n\
+                  12\n\
+                  <Composed Func>\n"
+        .to_string();
+    assert_eq!(stdout, expect);
+
+    assert_eq!(
+        run_code("(compose print 5)"),
+        "ArgumentError: Int is not callable\n".to_string()
+    );
+}
+
+#[test]
+fn test_conform() {
+    let stdout = run_file("examples/conform.lcore".to_string());
+    let expect = "True\n\
+                  3\n\
+                  expected Int, got String\n\
+                  expected String, got Int\n\
+                  missing\n\
+                  missing\n"
+        .to_string();
+    assert_eq!(stdout, expect);
+}
+
+#[test]
+fn test_contains() {
+    let stdout = run_file("examples/contains.lcore".to_string());
+    let expect = "True\n\
+                  False\n\
+                  True\n\
+                  True\n\
+                  False\n\
+                  True\n\
+                  True\n\
+                  True\n\
+                  False\n\
+                  ArgumentError\n"
+        .to_string();
+    assert_eq!(stdout, expect);
+}
+
+#[test]
+fn test_copy() {
+    let stdout = run_file("examples/copy.lcore".to_string());
+    let expect = "[100 { \"name\": \"changed\" }]\n\
+                  [1 { \"name\": \"inner\" }]\n\
+                  True\n"
+        .to_string();
+    assert_eq!(stdout, expect);
+}
+
+#[test]
+fn test_deep_size() {
+    let stdout = run_file("examples/deep-size.lcore".to_string());
+    let expect = "1\n\
+                  0\n\
+                  3\n\
+                  1\n\
+                  5\n\
+                  3\n\
+                  5\n\
+                  3\n"
+    .to_string();
+    assert_eq!(stdout, expect);
+}
+
+#[test]
+fn test_def_global() {
+    let stdout = run_file("examples/def-global.lcore".to_string());
+    let expect = "42\n\
+                  NameError\n\
+                  5\n\
+                  10\n"
+        .to_string();
+    assert_eq!(stdout, expect);
+}
+
+#[test]
+fn test_describe() {
+    let stdout = run_code("(set 'limit 3) (describe)");
+    let lines: Vec<&str> = stdout.lines().collect();
+    assert!(lines.contains(&"print: builtin"));
+    assert!(lines.contains(&"+: builtin"));
+    // User definitions come after all the builtins
+    assert_eq!(lines.last(), Some(&"limit: Int"));
+
+    assert_eq!(
+        run_code(
+            "(defn 'add ['x 'y] '[\"Adds.\" (+ x y)]) \
+             (describe \"+\") (describe 'add)"
+        ),
+        "+: builtin\n\
+         add: function [x y]\n  Adds.\n"
+            .to_string()
+    );
+
+    // Builtins bound to another name still know what they are
+    assert_eq!(
+        run_code("(set 'p print) (describe 'p) (print [(= p print) p])"),
+        "p: builtin print\n[True <Func print>]\n".to_string()
+    );
+}
+
+#[test]
+fn test_destructure() {
+    let stdout = run_file("examples/destructure.lcore".to_string());
+    let expect = "4\n\
+                  9\n\
+                  1\n\
+                  [2 3 4]\n\
+                  []\n\
+                  3\n"
+    .to_string();
+    assert_eq!(stdout, expect);
+
+    assert_eq!(
+        run_code("(set '[a b] [1 2 3])"),
+        "ArgumentError: Cannot destructure 3 values into 2 names\n"
+            .to_string()
+    );
+}
+
 #[test]
 fn test_dict() {
     let stdout = run_file("examples/dict.lcore".to_string());
@@ -158,6 +583,121 @@ fn test_dict() {
     );
 }
 
+#[test]
+fn test_dict_variant() {
+    let stdout = run_file("examples/dict-variant.lcore".to_string());
+    let expect = "1\n\
+                  3\n\
+                  2\n\
+                  True\n\
+                  2\n"
+    .to_string();
+    assert_eq!(stdout, expect);
+}
+
+#[test]
+fn test_diff() {
+    let stdout = run_file("examples/diff.lcore".to_string());
+    let expect = "Null\n\
+                  1\n\
+                  3\n\
+                  4\n\
+                  2\n\
+                  True\n\
+                  1\n\
+                  b\n\
+                  \"1\"\n"
+        .to_string();
+    assert_eq!(stdout, expect);
+}
+
+#[test]
+fn test_doc() {
+    let stdout = run_file("examples/doc.lcore".to_string());
+    let expect = "Returns x*x\n\
+                  16\n\
+                  Null\n\
+                  Hello\n\
+                  Null\n\
+                  Null\n"
+        .to_string();
+    assert_eq!(stdout, expect);
+
+    assert_eq!(
+        run_code("(doc 5)"),
+        "ArgumentError: Int is not a function\n".to_string()
+    );
+
+    // Only `[['args] [body] doc]` is a function, not any Array of that shape
+    assert_eq!(
+        run_code("(doc [[1] [2] 3])"),
+        "ArgumentError: Array is not a function\n".to_string()
+    );
+
+    assert_eq!(
+        run_code("(defn 'f ['x] 'y)"),
+        "ArgumentError: \"defn\" body must be a quoted Array\n".to_string()
+    );
+    assert_eq!(
+        run_code("(defn 'f [1] '[1])"),
+        "ArgumentError: \"defn\" parameters must be an Array of quoted names\n"
+            .to_string()
+    );
+}
+
+#[test]
+fn test_empty() {
+    let stdout = run_file("examples/empty.lcore".to_string());
+    let expect = "True\n\
+                  False\n\
+                  True\n\
+                  False\n\
+                  True\n\
+                  False\n\
+                  False\n\
+                  True\n\
+                  True\n"
+        .to_string();
+    assert_eq!(stdout, expect);
+
+    assert_eq!(
+        run_code("(print (empty? 11))"),
+        "ArgumentError: Int is not a collection\n".to_string()
+    );
+}
+
+#[test]
+fn test_env() {
+    let stdout = run_file("examples/env.lcore".to_string());
+    let expect = "5\n\
+                  <Func>\n\
+                  [5 10]\n"
+        .to_string();
+    assert_eq!(stdout, expect);
+}
+
+#[test]
+fn test_eprint() {
+    let code = "(eprintln \"oops\") (eprint [1 \"two\"]) (eprintln \"!\")";
+    let stderr = run_code_stderr(code);
+    assert_eq!(stderr, "oops\n[1 \"two\"]!\n");
+
+    let stdout = run_code(code);
+    assert_eq!(stdout, "");
+}
+
+#[test]
+fn test_equals_ignore_case() {
+    let stdout = run_file("examples/equals-ignore-case.lcore".to_string());
+    let expect = "True\n\
+                  True\n\
+                  False\n\
+                  False\n\
+                  \"=i\" takes two Strings, got String and Int\n"
+        .to_string();
+    assert_eq!(stdout, expect);
+}
+
 #[test]
 fn test_error() {
     let stdout = run_file("examples/error.lcore".to_string());
@@ -178,6 +718,18 @@ fn test_eval() {
     assert_eq!(stdout, expect);
 }
 
+#[test]
+fn test_every_nth() {
+    let stdout = run_file("examples/every-nth.lcore".to_string());
+    let expect = "[0 1 2 3 4 5]\n\
+                  [0 2 4]\n\
+                  [0 4]\n\
+                  [0]\n\
+                  Step must be positive (0)\n"
+        .to_string();
+    assert_eq!(stdout, expect);
+}
+
 #[test]
 fn test_fib() {
     let stdout = run_file("examples/fib.lcore".to_string());
@@ -185,22 +737,238 @@ fn test_fib() {
 }
 
 #[test]
-fn test_func() {
-    let stdout = run_file("examples/func.lcore".to_string());
-    let expect = "Hello Pebaz!\n\
-                  Hello 0!\n\
-                  Hello 1!\n\
-                  Hello 2!\n\
-                  Hello 88!\n\
-                  6\n"
-    .to_string();
+fn test_find_all() {
+    let stdout = run_file("examples/find-all.lcore".to_string());
+    let expect = "[1 3]\n\
+                  [0 3 6]\n\
+                  [1 3]\n\
+                  []\n\
+                  3\n\
+                  2\n\
+                  0\n\
+                  Empty pattern passed to \"find-all\"\n\
+                  Empty pattern passed to \"count-matches\"\n\
+                  \"find-all\" takes a String and a pattern\n"
+        .to_string();
     assert_eq!(stdout, expect);
 }
 
 #[test]
-fn test_get() {
-    let stdout = run_file("examples/get.lcore".to_string());
-    assert_eq!(stdout, "2\n".to_string());
+fn test_first_n() {
+    let stdout = run_file("examples/first-n.lcore".to_string());
+    let expect = "[1 2]\n\
+                  [3 4]\n\
+                  [1 2 3 4]\n\
+                  [1 2 3 4]\n\
+                  []\n\
+                  []\n\
+                  \"first-n\" takes an Array and an Int\n"
+        .to_string();
+    assert_eq!(stdout, expect);
+}
+
+#[test]
+fn test_flat_map() {
+    let stdout = run_file("examples/flat-map.lcore".to_string());
+    let expect = "[1 1 2 2 3 3]\n\
+                  [2 4]\n\
+                  []\n\
+                  \"flat-map\" function returned Int, not an Array\n\
+                  Int is not callable\n"
+        .to_string();
+    assert_eq!(stdout, expect);
+}
+
+#[test]
+fn test_format_error() {
+    assert_eq!(
+        run_code("(print undefined-name)"),
+        "NameError: Cannot lookup name: \"undefined-name\"\n".to_string()
+    );
+    assert_eq!(
+        run_code("(nth [1] 5)"),
+        "IndexError: Index out of bounds: got 5 but len is 1\n".to_string()
+    );
+    assert_eq!(
+        run_code("(+ 1)"),
+        "ArgumentError: \"+\" expected 2 arguments, got 1\n".to_string()
+    );
+    assert_eq!(
+        run_code("(get [1 2] 5)"),
+        "IndexError: Index out of bounds: got 5 but len is 2\n".to_string()
+    );
+    // Messages raised without their prefix get one
+    assert_eq!(
+        run_code("(get [1 2] \"a\")"),
+        "ArgumentError: Cannot index Array with String\n".to_string()
+    );
+    assert_eq!(
+        run_code("(assert-eq 1 2)"),
+        "LambdaCoreError: Assertion failed: expected 2 but got 1\n"
+            .to_string()
+    );
+    assert_eq!(
+        run_code("(return 1)"),
+        "ReturnError: Cannot return from outside of a function\n".to_string()
+    );
+    assert_eq!(
+        run_code("(break)"),
+        "BreakError: Cannot break from outside of a loop\n".to_string()
+    );
+}
+
+#[test]
+fn test_format_float() {
+    let stdout = run_file("examples/format-float.lcore".to_string());
+    let expect = "3.14\n\
+                  2\n\
+                  2.7\n\
+                  1.000\n\
+                  1.0\n\
+                  1.0\n\
+                  2.25\n\
+                  10000000000000000.0\n\
+                  0.0000001\n"
+        .to_string();
+    assert_eq!(stdout, expect);
+
+    // The formatter panics on precisions this large, so they're rejected
+    assert_eq!(
+        run_code("(format-float 1.5 70000)"),
+        "ArgumentError: Precision must be at most 1074, got 70000\n"
+            .to_string()
+    );
+}
+
+#[test]
+fn test_freeze() {
+    let stdout = run_file("examples/freeze.lcore".to_string());
+    let expect = "False\n\
+                  True\n\
+                  False\n\
+                  Cannot mutate frozen value \"primes\"\n\
+                  Cannot mutate frozen value \"primes\"\n\
+                  [2 3 5 7]\n\
+                  [1 3 5 7]\n\
+                  2\n\
+                  NameError\n\
+                  Cannot mutate frozen value \"primes\"\n\
+                  Cannot mutate frozen value \"primes\"\n\
+                  Cannot mutate frozen value \"primes\"\n\
+                  [2 3 5 7]\n\
+                  True\n\
+                  [0 6]\n"
+        .to_string();
+    assert_eq!(stdout, expect);
+}
+
+#[test]
+fn test_frequencies() {
+    let stdout = run_file("examples/frequencies.lcore".to_string());
+    let expect = "2\n\
+                  1\n\
+                  3\n\
+                  3\n\
+                  3\n\
+                  1\n\
+                  0\n\
+                  Array cannot be used as a Dict key\n"
+        .to_string();
+    assert_eq!(stdout, expect);
+}
+
+#[test]
+fn test_func() {
+    let stdout = run_file("examples/func.lcore".to_string());
+    let expect = "Hello Pebaz!\n\
+                  Hello 0!\n\
+                  Hello 1!\n\
+                  Hello 2!\n\
+                  Hello 88!\n\
+                  6\n"
+    .to_string();
+    assert_eq!(stdout, expect);
+}
+
+#[test]
+fn test_gensym() {
+    let stdout = run_file("examples/gensym.lcore".to_string());
+    let expect = "g__1\n\
+                  tmp__2\n\
+                  tmp__3\n\
+                  False\n"
+        .to_string();
+    assert_eq!(stdout, expect);
+}
+
+#[test]
+fn test_get() {
+    let stdout = run_file("examples/get.lcore".to_string());
+    assert_eq!(stdout, "2\n".to_string());
+}
+
+#[test]
+fn test_get_in() {
+    let stdout = run_file("examples/get-in.lcore".to_string());
+    let expect = "5433\n\
+                  localhost\n\
+                  2\n\
+                  Index out of bounds: got 5 but len is 2\n\
+                  [6543 5433]\n\
+                  [5432 5433]\n"
+        .to_string();
+    assert_eq!(stdout, expect);
+}
+
+#[test]
+fn test_get_path() {
+    let stdout = run_file("examples/get-path.lcore".to_string());
+    let expect = "lcore\n\
+                  alpha\n\
+                  443\n\
+                  beta\n\
+                  Not Found\n\
+                  Null\n\
+                  Null\n\
+                  Null\n\
+                  True\n"
+        .to_string();
+    assert_eq!(stdout, expect);
+}
+
+#[test]
+fn test_group_by() {
+    let stdout = run_file("examples/group-by.lcore".to_string());
+    let expect = "[2 4 6]\n\
+                  [1 3 5]\n\
+                  2\n\
+                  [1 2]\n\
+                  [5 4 3]\n\
+                  0\n"
+    .to_string();
+    assert_eq!(stdout, expect);
+
+    assert_eq!(
+        run_code("(defn 'wrap ['x] '[[x]]) (group-by wrap [1 2])"),
+        "ArgumentError: Array cannot be used as a Dict key\n".to_string()
+    );
+}
+
+#[test]
+fn test_hash_of() {
+    let stdout = run_file("examples/hash-of.lcore".to_string());
+    let expect = "True\n\
+                  True\n\
+                  True\n\
+                  False\n\
+                  False\n"
+        .to_string();
+    assert_eq!(stdout, expect);
+
+    assert_eq!(
+        run_code("(hash-of [1 2])"),
+        "ArgumentError: Array is not hashable\n".to_string()
+    );
 }
 
 #[test]
@@ -209,6 +977,21 @@ fn test_hello_world() {
     assert_eq!(stdout, "Hello World!\n".to_string());
 }
 
+#[test]
+fn test_hex() {
+    let stdout = run_file("examples/hex.lcore".to_string());
+    let expect = "4142\n\
+                  AB\n\
+                  68c3a96c6c6f\n\
+                  héllo\n\
+                  JK\n\
+                  Odd number of hex digits (3)\n\
+                  Invalid hex digits \"zz\"\n\
+                  \"hex-encode\" takes a String\n"
+        .to_string();
+    assert_eq!(stdout, expect);
+}
+
 #[test]
 fn test_if() {
     let stdout = run_file("examples/if.lcore".to_string());
@@ -229,18 +1012,219 @@ fn test_import() {
     assert_eq!(stdout, expect);
 }
 
+#[test]
+fn test_import_circular() {
+    // cycle-a imports cycle-b, which imports cycle-a again
+    let stdout =
+        run_code("(import \"examples/cycle-a.lcore\") (print (from-a))");
+    assert_eq!(
+        stdout,
+        "LambdaCoreError: circular import of \"examples/cycle-a.lcore\"\n\
+         a\n"
+    );
+}
+
+#[test]
+fn test_import_as() {
+    let stdout = run_file("examples/import-as.lcore".to_string());
+    let expect = "You are importing the `add` function!\n\
+                  3\n\
+                  7\n\
+                  False\n\
+                  False\n\
+                  12\n\
+                  False\n\
+                  12\n"
+        .to_string();
+    assert_eq!(stdout, expect);
+}
+
+#[test]
+fn test_import_dict() {
+    let stdout = run_file("examples/import-dict.lcore".to_string());
+    let expect = "You are importing the `add` function!\n\
+                  1\n\
+                  True\n\
+                  False\n\
+                  5\n\
+                  1\n"
+    .to_string();
+    assert_eq!(stdout, expect);
+}
+
+#[test]
+fn test_index_of() {
+    let stdout = run_file("examples/index-of.lcore".to_string());
+    let expect = "1\n\
+                  2\n\
+                  4\n\
+                  -1\n\
+                  0\n\
+                  3\n\
+                  -1\n"
+        .to_string();
+    assert_eq!(stdout, expect);
+}
+
+#[test]
+fn test_insert() {
+    let stdout = run_file("examples/insert.lcore".to_string());
+    let expect = "[0 1 3]\n\
+                  [1 2 3]\n\
+                  [1 3 4]\n\
+                  [1 3]\n\
+                  [1 3]\n\
+                  Index out of bounds: got 3 but len is 2\n\
+                  Index out of bounds: got 2 but len is 2\n"
+        .to_string();
+    assert_eq!(stdout, expect);
+}
+
+#[test]
+fn test_interleave() {
+    let stdout = run_file("examples/interleave.lcore".to_string());
+    let expect = "[1 \"a\" 2 \"b\" 3 \"c\"]\n\
+                  [1 \"a\" 2 \"b\"]\n\
+                  [1 \"a\" True 2 \"b\" False]\n\
+                  []\n\
+                  [1 2 3 [4 5]]\n\
+                  \"interleave\" takes Arrays, got Int\n"
+        .to_string();
+    assert_eq!(stdout, expect);
+}
+
+#[test]
+fn test_iterate() {
+    let stdout = run_file("examples/iterate.lcore".to_string());
+    let expect = "[1 2 4 8 16 32 64 128 256 512]\n\
+                  []\n\
+                  [1]\n\
+                  [0 3 6 9]\n\
+                  Count must not be negative (-1)\n\
+                  Invalid operands (String and Int)\n"
+        .to_string();
+    assert_eq!(stdout, expect);
+}
+
 #[test]
 fn test_len() {
     let stdout = run_file("examples/len.lcore".to_string());
     assert_eq!(stdout, "3\n".to_string());
 }
 
+#[test]
+fn test_let_star() {
+    let stdout = run_file("examples/let-star.lcore".to_string());
+    let expect = "21\n\
+                  hi there\n\
+                  outer\n\
+                  outer\n\
+                  False\n"
+        .to_string();
+    assert_eq!(stdout, expect);
+
+    assert_eq!(
+        run_code("(let* '[a 1 b] '[a])"),
+        "ArgumentError: \"let*\" has no value for \"b\"\n".to_string()
+    );
+}
+
+#[test]
+fn test_lines() {
+    assert_eq!(
+        run_code("(print (split-lines \"one\ntwo\n\nfour\n\"))"),
+        "[\"one\" \"two\" \"\" \"four\"]\n".to_string()
+    );
+    assert_eq!(
+        run_code("(print (split-lines \"one\r\ntwo\r\n\"))"),
+        "[\"one\" \"two\"]\n".to_string()
+    );
+
+    with_temp_dir("lcore_test_lines", |dir| {
+        let file = dir.join("lines.txt");
+        std::fs::write(&file, "alpha\r\nbeta\ngamma").unwrap();
+        assert_eq!(
+            run_code(&format!("(print (read-lines {:?}))", file)),
+            "[\"alpha\" \"beta\" \"gamma\"]\n".to_string()
+        );
+    });
+
+    assert!(run_code("(read-lines \"does/not/exist\")")
+        .starts_with("LambdaCoreError: does/not/exist: "));
+}
+
+#[test]
+fn test_literals() {
+    let stdout = run_file("examples/literals.lcore".to_string());
+    let expect = "[True True True False False]\n\
+                  [Null Null]\n\
+                  True\n\
+                  True\n\
+                  still an identifier\n\
+                  0\n"
+    .to_string();
+    assert_eq!(stdout, expect);
+}
+
+#[test]
+fn test_load_once() {
+    let stdout = run_file("examples/load-once.lcore".to_string());
+    let expect = "You are importing the `add` function!\n\
+                  10\n\
+                  2\n"
+    .to_string();
+    assert_eq!(stdout, expect);
+}
+
+#[test]
+fn test_loc() {
+    let stdout = run_file("examples/loc.lcore".to_string());
+    assert_eq!(stdout, "4\n".to_string());
+
+    let file = std::env::temp_dir().join("lcore_test_loc.lcore");
+    std::fs::write(&file, "(print 1)\n\n:: comment\n(print 2)\n").unwrap();
+    assert_eq!(
+        run_code(&format!("(print (loc {:?}))", file)),
+        "4\n".to_string()
+    );
+
+    std::fs::write(&file, "(print 1").unwrap();
+    assert!(run_code(&format!("(loc {:?})", file))
+        .starts_with("LambdaCoreError: Failed to parse"));
+    std::fs::remove_file(&file).unwrap();
+}
+
 #[test]
 fn test_loop() {
     let stdout = run_file("examples/loop.lcore".to_string());
     assert_eq!(stdout, "0\n1\n2\n".to_string());
 }
 
+#[test]
+fn test_map_dict() {
+    let stdout = run_file("examples/map-dict.lcore".to_string());
+    let expect = "2\n\
+                  3\n\
+                  1\n\
+                  2\n\
+                  2\n"
+    .to_string();
+    assert_eq!(stdout, expect);
+
+    assert_eq!(
+        run_code(
+            "(defn 'same ['k] '[\"k\"]) (map-keys same (dict 'a 1 'b 2))"
+        ),
+        "ArgumentError: \"map-keys\" mapped two keys to the same key\n"
+            .to_string()
+    );
+    assert_eq!(
+        run_code("(map-values len [1 2])"),
+        "ArgumentError: \"map-values\" takes a function and a Dict\n"
+            .to_string()
+    );
+}
+
 #[test]
 fn test_math() {
     let stdout = run_file("examples/math.lcore".to_string());
@@ -254,11 +1238,11 @@ fn test_math() {
                   \n\
                   --------------------\n\
                   Float\n\
-                  6\n\
-                  -2\n\
-                  8\n\
+                  6.0\n\
+                  -2.0\n\
+                  8.0\n\
                   0.5\n\
-                  16\n\
+                  16.0\n\
                   \n\
                   --------------------\n\
                   String\n\
@@ -274,68 +1258,660 @@ fn test_math() {
 }
 
 #[test]
-fn test_order() {
-    let stdout = run_file("examples/order.lcore".to_string());
-    let expect = "First\n\
-                  Second\n\
-                  Null\n\
-                  3\n\
-                  [1 2 \"Three\"]\n"
-        .to_string();
+fn test_memoize() {
+    let stdout = run_file("examples/memoize.lcore".to_string());
+    let expect = "610\n\
+                  1973\n\
+                  610\n\
+                  16\n\
+                  610\n\
+                  16\n\
+                  1548008755920\n\
+                  2\n"
+    .to_string();
     assert_eq!(stdout, expect);
 }
 
-//#[test]
-#[allow(dead_code)]
-fn test_print() {
-    let stdout = run_file("examples/order.lcore".to_string());
-
-    // TODO(pebaz): Use a regex for this:
-    let expect = "String: Lambda Core version 0.1.0\n\
-                  Boolean: True\n\
-                  Integer: 11\n\
-                  Float: 3.14\n\
-                  Null: Null\n\
-                  Array: [1 2 [3 4]]\n\
-                  __repr__ String: [\"This Should Be Quoted\"]\n\
-                  Function: <Func at 0x1e3958a5d78>\n\
-                  Return Value: 11\n\
-                  hello\\nworld!\n"
+#[test]
+fn test_min_by() {
+    let stdout = run_file("examples/min-by.lcore".to_string());
+    let expect = "abc\n\
+                  a\n\
+                  7\n\
+                  -2\n\
+                  2.5\n"
         .to_string();
     assert_eq!(stdout, expect);
+
+    assert_eq!(
+        run_code("(min-by len [])"),
+        "ArgumentError: \"min-by\" of an empty Array\n".to_string()
+    );
+    assert_eq!(
+        run_code("(defn 'id ['x] '[x]) (max-by id [1 \"a\"])"),
+        "ArgumentError: Cannot compare String and Int\n".to_string()
+    );
 }
 
 #[test]
-fn test_quote() {
-    let stdout = run_file("examples/quote.lcore".to_string());
-    let expect = "3\n\
-                  quoted-thing: (quote [1 2 3])\n\
-                  (quote [( print \"Hello World!\" )])\n"
+fn test_nth() {
+    let stdout = run_file("examples/nth.lcore".to_string());
+    let expect = "1\n\
+                  2\n\
+                  3\n\
+                  1\n\
+                  Index out of bounds: got 3 but len is 3\n\
+                  Index out of bounds: got -4 but len is 3\n\
+                  3\n\
+                  \"last\" of an empty Array\n\
+                  \"nth\" takes an Array and an Int\n"
         .to_string();
     assert_eq!(stdout, expect);
 }
 
 #[test]
-fn test_ret() {
-    let stdout = run_file("examples/ret.lcore".to_string());
-    assert_eq!(stdout, "-2\n6\n-2\n".to_string());
-}
-
-#[test]
-fn test_sel() {
-    let stdout = run_file("examples/sel.lcore".to_string());
-    assert_eq!(stdout, "It's Three!\n55\n".to_string());
+fn test_number() {
+    let stdout = run_file("examples/number.lcore".to_string());
+    let expect = "True\n\
+                  True\n\
+                  False\n\
+                  False\n\
+                  True\n\
+                  True\n\
+                  True\n\
+                  False\n\
+                  False\n\
+                  False\n\
+                  \"numeric-string?\" takes a String\n"
+        .to_string();
+    assert_eq!(stdout, expect);
 }
 
 #[test]
-fn test_stdlib() {
-    let stdout = run_file("examples/stdlib.lcore".to_string());
-    assert_eq!(stdout, "256\n".to_string());
+fn test_or_else() {
+    let stdout = run_file("examples/or-else.lcore".to_string());
+    let expect = "2\n\
+                  1\n\
+                  third\n\
+                  Null\n\
+                  Null\n\
+                  False\n\
+                  0\n"
+    .to_string();
+    assert_eq!(stdout, expect);
 }
 
 #[test]
-fn test_swap() {
-    let stdout = run_file("examples/swap.lcore".to_string());
+fn test_order() {
+    let stdout = run_file("examples/order.lcore".to_string());
+    let expect = "First\n\
+                  Second\n\
+                  Null\n\
+                  3\n\
+                  [1 2.0 \"Three\"]\n"
+        .to_string();
+    assert_eq!(stdout, expect);
+}
+
+#[test]
+fn test_output_sink() {
+    let stdout = run_file("examples/output-sink.lcore".to_string());
+    let expect = "Hello, sink\n\
+                  x: Int\n\
+                  restored\n"
+        .to_string();
+    assert_eq!(stdout, expect);
+}
+
+#[test]
+fn test_pad() {
+    let stdout = run_file("examples/pad.lcore".to_string());
+    let expect = "007\n\
+                  ab  |\n\
+                  wide\n\
+                  ..αβ\n\
+                  ababax\n\
+                  xababa\n"
+        .to_string();
+    assert_eq!(stdout, expect);
+
+    assert_eq!(
+        run_code("(pad-left \"a\" 3 \"\")"),
+        "ArgumentError: \"pad-left\" pad must be a non-empty String\n"
+            .to_string()
+    );
+}
+
+#[test]
+fn test_pairs() {
+    let stdout = run_file("examples/pairs.lcore".to_string());
+    let expect = "2\n\
+                  True\n\
+                  [[\"only\" 1]]\n\
+                  11\n\
+                  two\n\
+                  0\n\
+                  Expected a [key value] pair, got Array\n\
+                  Array cannot be used as a Dict key\n"
+        .to_string();
+    assert_eq!(stdout, expect);
+}
+
+#[test]
+fn test_parse_ok() {
+    let stdout = run_file("examples/parse-ok.lcore".to_string());
+    let expect = "True\n\
+                  False\n\
+                  False\n\
+                  Null\n \
+                  --> 1:12\n \
+                  --> 1:10\n\
+                  \"parse-ok?\" takes a String\n\
+                  False\n\
+                  ArgumentError: Duplicate key 1 in Dict literal\n"
+        .to_string();
+    assert_eq!(stdout, expect);
+}
+
+#[test]
+fn test_parameter_scope() {
+    let stdout = run_file("examples/parameter-scope.lcore".to_string());
+    let expect = "1\n\
+                  10\n\
+                  0\n\
+                  1\n\
+                  2\n"
+    .to_string();
+    assert_eq!(stdout, expect);
+}
+
+#[test]
+fn test_partial() {
+    let stdout = run_file("examples/partial.lcore".to_string());
+    let expect = "15\n\
+                  Hello Pebaz\n\
+                  Hello Pebaz\n\
+                  <Partial Func>\n"
+        .to_string();
+    assert_eq!(stdout, expect);
+
+    assert_eq!(
+        run_code("(partial 10 5)"),
+        "ArgumentError: Int is not callable\n".to_string()
+    );
+}
+
+#[test]
+fn test_path_predicates() {
+    with_temp_dir("lcore_test_path_predicates", |dir| {
+        let file = dir.join("file.txt");
+        std::fs::write(&file, "contents").unwrap();
+        let missing = dir.join("missing");
+
+        let code = format!(
+            "(print [(exists? {0:?}) (is-file? {0:?}) (is-dir? {0:?})]) \
+             (print [(exists? {1:?}) (is-file? {1:?}) (is-dir? {1:?})]) \
+             (print [(exists? {2:?}) (is-file? {2:?}) (is-dir? {2:?})])",
+            file, dir, missing
+        );
+        assert_eq!(
+            run_code(&code),
+            "[True True False]\n\
+             [True False True]\n\
+             [False False False]\n"
+                .to_string()
+        );
+        assert_eq!(
+            run_code("(exists? 1)"),
+            "ArgumentError: \"exists?\" takes a path String\n".to_string()
+        );
+    });
+}
+
+#[test]
+fn test_pipe() {
+    let stdout = run_file("examples/pipe.lcore".to_string());
+    let expect = "12\n\
+                  11\n\
+                  15\n\
+                  10\n"
+        .to_string();
+    assert_eq!(stdout, expect);
+
+    assert_eq!(
+        run_code("(print (pipe 5 len \"oops\"))"),
+        "ArgumentError: Int has no length\n".to_string()
+    );
+    assert_eq!(
+        run_code("(print (pipe 5 \"oops\"))"),
+        "ArgumentError: String is not callable\n".to_string()
+    );
+}
+
+#[test]
+fn test_print() {
+    let stdout = run_file("examples/print.lcore".to_string());
+    let expect = "String: Lambda Core version 0.1.0\n\
+                  Boolean: True\n\
+                  Integer: 11\n\
+                  Float: 3.14\n\
+                  Null: Null\n\
+                  Array: [1 2 [3 4]]\n\
+                  __repr__ String: [\"This Should Be Quoted\"]\n\
+                  Function: <Func print>\n\
+                  Return Value: 11\n\
+                  hello\\nworld!\n"
+        .to_string();
+    assert_eq!(stdout, expect);
+}
+
+#[test]
+fn test_quote() {
+    let stdout = run_file("examples/quote.lcore".to_string());
+    let expect = "3\n\
+                  quoted-thing: '[1 2 3]\n\
+                  '[(print \"Hello World!\")]\n"
+        .to_string();
+    assert_eq!(stdout, expect);
+}
+
+#[test]
+fn test_read() {
+    let stdout = run_file("examples/read.lcore".to_string());
+    let expect = "[1 2.5 True Null]\n\
+                  'x\n\
+                  [(+ 1 2)]\n\
+                  [True]\n\
+                  1000.0\n\
+                  '[(print \"hi\")]\n\
+                  True\n\
+                  ArgumentError\n\
+                  Number 99999999999999999999 is out of range\n\
+                  Array cannot be used as a Dict key\n\
+                  Duplicate key 1 in Dict literal\n"
+        .to_string();
+    assert_eq!(stdout, expect);
+
+    // Every value `repr` prints reads back as an equal value
+    let values = [
+        "Null",
+        "True",
+        "-42",
+        "2.5",
+        "1e300",
+        "\"text with spaces\"",
+        "#\\a",
+        "#\\space",
+        "[]",
+        "[1 [2 [3 \"deep\"]] #\\newline]",
+        "'name",
+        "''name",
+        "'[(+ 1 (* 2 3))]",
+        "'[1 'inner [(f)]]",
+        "(dict)",
+        "(dict \"a\" 1 \"b\" [1 2.0] \"c\" 'x)",
+        "(dict \"outer\" (dict 1 (dict #\\k Null)))",
+        "[(dict \"in\" \"array\") 'x]",
+    ];
+    for value in values.iter() {
+        let code = format!("(set 'v {}) (print (= v (read (repr v))))", value);
+        assert_eq!(run_code(&code), "True\n", "round trip of {}", value);
+    }
+}
+
+#[test]
+fn test_read_dir() {
+    with_temp_dir("lcore_test_read_dir", |dir| {
+        std::fs::create_dir(dir.join("nested")).unwrap();
+        std::fs::write(dir.join("b.txt"), "").unwrap();
+        std::fs::write(dir.join("a.txt"), "").unwrap();
+
+        assert_eq!(
+            run_code(&format!("(print (read-dir {:?}))", dir)),
+            "[\"a.txt\" \"b.txt\" \"nested\"]\n".to_string()
+        );
+        assert_eq!(
+            run_code(&format!(
+                "(print (abs-path {:?}))",
+                dir.join("nested/..")
+            )),
+            format!("{}\n", dir.canonicalize().unwrap().display())
+        );
+        assert!(run_code(&format!("(read-dir {:?})", dir.join("a.txt")))
+            .starts_with("LambdaCoreError: "));
+        assert!(run_code("(read-dir \"does/not/exist\")")
+            .starts_with("LambdaCoreError: does/not/exist: "));
+    });
+}
+
+#[test]
+fn test_recursion_limit() {
+    let stdout = run_file("examples/recursion-limit.lcore".to_string());
+    let expect = "recursion limit exceeded (100)\n\
+                  recursion limit exceeded (100)\n\
+                  99\n\
+                  recursion limit exceeded (100)\n\
+                  1000\n\
+                  \"set-recursion-limit\" takes a positive Int\n"
+        .to_string();
+    assert_eq!(stdout, expect);
+
+    // Runaway recursion reports an error instead of overflowing the stack
+    let stdout =
+        run_code("(set-recursion-limit 50) (defn 'f ['n] '[(f n)]) (f 1)");
+    assert_eq!(stdout, "LambdaCoreError: recursion limit exceeded (50)\n");
+
+    // The interpreter's stack fits the default limit. `g` is a parameter, so
+    // looking it up doesn't walk every enclosing scope and the test stays fast
+    let stdout = run_code("(defn 'f ['g] '[(g g)]) (f f)");
+    assert_eq!(
+        stdout,
+        "LambdaCoreError: recursion limit exceeded (10000)\n".to_string()
+    );
+}
+
+#[test]
+fn test_reduce_kv() {
+    let stdout = run_file("examples/reduce-kv.lcore".to_string());
+    let expect = "12\n\
+                  13\n\
+                  only!\n\
+                  10\n\
+                  ArgumentError\n\
+                  Int is not callable\n"
+        .to_string();
+    assert_eq!(stdout, expect);
+}
+
+#[test]
+fn test_regex() {
+    let stdout = run_file("examples/regex.lcore".to_string());
+    let expect = "True\n\
+                  False\n\
+                  True\n\
+                  [\"bob@example.com\" \"bob\" \"example\"]\n\
+                  [\"xz\" Null]\n\
+                  Null\n\
+                  a b c\n\
+                  1=a 2=b\n"
+        .to_string();
+    assert_eq!(stdout, expect);
+
+    assert_eq!(
+        run_code("(re-match \"(ab\" \"ab\")"),
+        "ArgumentError: Invalid pattern \"(ab\": unclosed group\n".to_string()
+    );
+
+    // An unbalanced pattern must not close the group that anchors it
+    assert_eq!(
+        run_code("(re-match \"a)|(?:b\" \"axxxx\")"),
+        "ArgumentError: Invalid pattern \"a)|(?:b\": unopened group\n"
+            .to_string()
+    );
+
+    // Nested repetition must not backtrack exponentially
+    assert_eq!(
+        run_code(
+            "(print (re-match \"(a*)*b\" \"aaaaaaaaaaaaaaaaaaaaaaaaaaaaaa\"))"
+        ),
+        "False\n".to_string()
+    );
+}
+
+#[test]
+fn test_replace() {
+    let stdout = run_file("examples/replace.lcore".to_string());
+    let expect = "one cat two cat\n\
+                  bb\n\
+                  unchanged\n\
+                  one cat two fish\n"
+        .to_string();
+    assert_eq!(stdout, expect);
+
+    assert_eq!(
+        run_code("(replace \"abc\" \"\" \"x\")"),
+        "ArgumentError: Empty pattern passed to \"replace\"\n".to_string()
+    );
+}
+
+#[test]
+fn test_repr() {
+    let stdout = run_file("examples/repr.lcore".to_string());
+    let expect = "\"hi\"\n\
+                  hi\n\
+                  4\n\
+                  2\n\
+                  [1 \"two\" [3.0 #\\a]]\n\
+                  [1 \"two\" [3.0 #\\a]]\n\
+                  { \"k\": \"v\" }\n\
+                  42\n"
+        .to_string();
+    assert_eq!(stdout, expect);
+}
+
+#[test]
+fn test_ret() {
+    let stdout = run_file("examples/ret.lcore".to_string());
+    assert_eq!(stdout, "-2\n6\n-2\n".to_string());
+}
+
+#[test]
+fn test_return() {
+    let stdout = run_file("examples/return.lcore".to_string());
+    let expect = "negative\n\
+                  zero\n\
+                  positive\n\
+                  3\n\
+                  Null\n"
+        .to_string();
+    assert_eq!(stdout, expect);
+
+    assert_eq!(
+        run_code("(print 1) (return 5) (print 2)"),
+        "1\nReturnError: Cannot return from outside of a function\n"
+            .to_string()
+    );
+}
+
+#[test]
+fn test_rotate() {
+    let stdout = run_file("examples/rotate.lcore".to_string());
+    let expect = "[2 3 4 1]\n\
+                  [4 1 2 3]\n\
+                  [1 2 3 4]\n\
+                  [3 4 1 2]\n\
+                  [4 1 2 3]\n\
+                  []\n\
+                  \"rotate\" takes an Array and an Int\n"
+        .to_string();
+    assert_eq!(stdout, expect);
+}
+
+#[test]
+fn test_run_tests() {
+    let stdout = run_file("examples/run-tests.lcore".to_string());
+    let expect = "PASS addition\n\
+                  FAIL strings: LambdaCoreError: Assertion failed: expected \"ba\" but got \"ab\"\n\
+                  PASS scoped\n\
+                  3 tests, 1 failed\n\
+                  1\n\
+                  False\n"
+        .to_string();
+    assert_eq!(stdout, expect);
+}
+
+#[test]
+fn test_scan() {
+    let stdout = run_file("examples/scan.lcore".to_string());
+    let expect = "[0 1 3 6]\n\
+                  [0 3 3 4 4 5 5]\n\
+                  [10]\n\
+                  \"scan\" takes a function, an initial value and an Array\n\
+                  Int is not callable\n"
+        .to_string();
+    assert_eq!(stdout, expect);
+}
+
+#[test]
+fn test_scopes() {
+    let stdout = run_file("examples/scopes.lcore".to_string());
+    let expect = "[\"hello\" 1 2]\n\
+                  hello\n"
+        .to_string();
+    assert_eq!(stdout, expect);
+
+    assert_eq!(
+        run_code("(defn 'f [] '[missing]) (f)"),
+        "NameError: Cannot lookup name: \"missing\"\n".to_string()
+    );
+}
+
+#[test]
+fn test_sel() {
+    let stdout = run_file("examples/sel.lcore".to_string());
+    assert_eq!(stdout, "It's Three!\n55\n".to_string());
+}
+
+#[test]
+fn test_select() {
+    let stdout = run_file("examples/select.lcore".to_string());
+    let expect = "yes\n\
+                  no\n\
+                  [1]\n\
+                  evaluated a\n\
+                  evaluated b\n"
+        .to_string();
+    assert_eq!(stdout, expect);
+
+    assert_eq!(
+        run_code("(select 1 2 3)"),
+        "ArgumentError: \"select\" condition must be a Boolean, got Int\n"
+            .to_string()
+    );
+}
+
+#[test]
+fn test_select_keys() {
+    let stdout = run_file("examples/select-keys.lcore".to_string());
+    let expect = "2\n\
+                  1\n\
+                  3\n\
+                  1\n\
+                  {  }\n"
+        .to_string();
+    assert_eq!(stdout, expect);
+
+    assert_eq!(
+        run_code("(select-keys [1 2] ['a])"),
+        "ArgumentError: \"select-keys\" takes a Dict and an Array\n"
+            .to_string()
+    );
+}
+
+#[test]
+fn test_set_in() {
+    let stdout = run_file("examples/set-in.lcore".to_string());
+    let expect = "[[1 2] [5 4]]\n\
+                  [[1 2] [3 4]]\n\
+                  [[1 2] [3 { \"inner\": \"Huzzah!\" }]]\n\
+                  8080\n\
+                  80\n"
+        .to_string();
+    assert_eq!(stdout, expect);
+
+    assert_eq!(
+        run_code("(set-in [1 2] [2] 5)"),
+        "IndexError: Index out of bounds: got 2 but len is 2\n".to_string()
+    );
+    assert_eq!(
+        run_code("(set-in [1 2] [-5] 0)"),
+        "IndexError: Index out of bounds: got -5 but len is 2\n".to_string()
+    );
+    assert_eq!(
+        run_code("(set-in [] [-1] 5)"),
+        "IndexError: Index out of bounds: got -1 but len is 0\n".to_string()
+    );
+    assert_eq!(run_code("(print (set-in [1 2] [-2] 0))"), "[0 2]\n");
+}
+
+#[test]
+fn test_short_circuit() {
+    let stdout = run_file("examples/short-circuit.lcore".to_string());
+    let expect = "True\n\
+                  False\n\
+                  False\n\
+                  False\n\
+                  True\n\
+                  True\n\
+                  False\n\
+                  [True False]\n\
+                  \"and*\" operands must be Booleans, got Int\n"
+        .to_string();
+    assert_eq!(stdout, expect);
+}
+
+#[test]
+fn test_sort_unique() {
+    let stdout = run_file("examples/sort-unique.lcore".to_string());
+    let expect = "[1 2 3]\n\
+                  [\"a\" \"b\" \"c\"]\n\
+                  []\n\
+                  [1 2 3 4 5 6]\n\
+                  [1 2 2 2 3 4 4]\n\
+                  [1 2]\n\
+                  [1 2 3 4]\n\
+                  Cannot compare String and Int\n\
+                  Cannot compare Int and String\n"
+        .to_string();
+    assert_eq!(stdout, expect);
+}
+
+#[test]
+fn test_split_at() {
+    let stdout = run_file("examples/split-at.lcore".to_string());
+    let expect = "[[1 2] [3 4]]\n\
+                  [[] [1 2 3 4]]\n\
+                  [[1 2 3 4] []]\n\
+                  [[1 2 3] [4]]\n\
+                  [[] [1 2 3 4]]\n\
+                  \"split-at\" takes an Array and an Int\n"
+        .to_string();
+    assert_eq!(stdout, expect);
+}
+
+#[test]
+fn test_stdlib() {
+    let stdout = run_file("examples/stdlib.lcore".to_string());
+    assert_eq!(stdout, "256\n".to_string());
+}
+
+#[test]
+fn test_struct() {
+    let stdout = run_file("examples/struct.lcore".to_string());
+    let expect = "Person { name: \"Pebaz\", age: 24 }\n\
+                  Pebaz\n\
+                  24\n\
+                  25\n\
+                  24\n\
+                  Samuel\n\
+                  True\n\
+                  <Struct Person>\n"
+        .to_string();
+    assert_eq!(stdout, expect);
+
+    assert_eq!(
+        run_code("(defstruct 'Point '[x y]) (Point 1)"),
+        "ArgumentError: \"Point\" takes 2 fields, got 1\n".to_string()
+    );
+    assert_eq!(
+        run_code("(defstruct 'Point '[x y]) (get-field (Point 1 2) 'z)"),
+        "NameError: No field named: \"z\"\n".to_string()
+    );
+}
+
+#[test]
+fn test_swap() {
+    let stdout = run_file("examples/swap.lcore".to_string());
     let expect = "Swapping Nest Level: 1\n\
         Before: 3\n\
         After:  4\n\
@@ -353,3 +1929,230 @@ fn test_swap() {
         After:  { \"inner2\": { \"inner3\": [\"SO MUCH INNER\" { \"like-so-much-inner\": \"VICTORY\" }] } }\n".to_string();
     assert_eq!(stdout, expect);
 }
+
+#[test]
+fn test_system() {
+    let stdout = run_file("examples/system.lcore".to_string());
+    let expect = "hello world\n\
+                  \n\
+                  0\n\
+                  0\n\
+                  1\n\
+                  LambdaCoreError\n"
+        .to_string();
+    assert_eq!(stdout, expect);
+}
+
+#[test]
+fn test_thread() {
+    let stdout = run_file("examples/thread.lcore".to_string());
+    let expect = "12\n\
+                  -5\n\
+                  [1 2 3 4]\n\
+                  5\n\
+                  [4 3 1 2]\n\
+                  8\n\
+                  9\n"
+    .to_string();
+    assert_eq!(stdout, expect);
+
+    assert_eq!(
+        run_code("(-> 5 '[(+ 1) (* 2)])"),
+        "ArgumentError: \"->\" expects quoted calls like '(f x)\n".to_string()
+    );
+    assert_eq!(
+        run_code("(->> 5 10)"),
+        "ArgumentError: \"->>\" expects quoted calls like '(f x)\n"
+            .to_string()
+    );
+}
+
+#[test]
+fn test_three_way() {
+    let stdout = run_file("examples/three-way.lcore".to_string());
+    let expect = "-1\n\
+                  0\n\
+                  1\n\
+                  0\n\
+                  -1\n\
+                  1\n\
+                  -1\n\
+                  0\n\
+                  Cannot compare Int and String\n"
+        .to_string();
+    assert_eq!(stdout, expect);
+}
+
+#[test]
+fn test_time() {
+    let stdout = run_file("examples/time.lcore".to_string());
+    let expect = "working\n\
+                  42\n"
+        .to_string();
+    assert_eq!(stdout, expect);
+
+    let stderr = run_code_stderr("(time '[(+ 1 2)])");
+    assert!(stderr.starts_with("Elapsed time: "));
+    assert_eq!(stderr.lines().count(), 1);
+}
+
+#[test]
+fn test_to_hex() {
+    let stdout = run_file("examples/to-hex.lcore".to_string());
+    let expect = "ff\n\
+                  10\n\
+                  1010\n\
+                  [\"0\" \"0\" \"0\"]\n\
+                  [\"-ff\" \"-10\" \"-1010\"]\n\
+                  -7fffffffffffffff\n\
+                  \"to-bin\" takes an Int\n"
+        .to_string();
+    assert_eq!(stdout, expect);
+}
+
+#[test]
+fn test_trace() {
+    let stdout = run_file("examples/trace.lcore".to_string());
+    let expect = "3\n\
+                  ab\n\
+                  7\n\
+                  15\n"
+        .to_string();
+    assert_eq!(stdout, expect);
+
+    let stderr = run_code_stderr(
+        "(defn 'add ['x 'y] '[(+ x y)]) \
+         (trace 'add) (add 1 2) (add \"a\" \"b\") \
+         (untrace 'add) (add 3 4)",
+    );
+    let expect = "TRACE: (add 1 2)\n\
+                  TRACE: add => 3\n\
+                  TRACE: (add \"a\" \"b\")\n\
+                  TRACE: add => \"ab\"\n"
+        .to_string();
+    assert_eq!(stderr, expect);
+
+    // Errors from the traced function still propagate
+    assert_eq!(
+        run_code("(defn 'f ['x] '[(get-field x 'y)]) (trace 'f) (f 1)"),
+        "ArgumentError: Int is not a Struct\n".to_string()
+    );
+}
+
+#[test]
+fn test_trim() {
+    let stdout = run_file("examples/trim.lcore".to_string());
+    let expect = "|x|\n\
+                  |x  |\n\
+                  |  x|\n\
+                  x\n\
+                  x\n\
+                  x*x\n\
+                  |x|\n\
+                  \"trim-start\" takes a String\n"
+        .to_string();
+    assert_eq!(stdout, expect);
+}
+
+#[test]
+fn test_truthy() {
+    let stdout = run_file("examples/truthy.lcore".to_string());
+    let expect = "False\n\
+                  False\n\
+                  False\n\
+                  False\n\
+                  False\n\
+                  False\n\
+                  False\n\
+                  True\n\
+                  True\n\
+                  True\n\
+                  True\n\
+                  True\n\
+                  non-empty\n"
+        .to_string();
+    assert_eq!(stdout, expect);
+}
+
+#[test]
+fn test_try() {
+    let stdout = run_file("examples/try.lcore".to_string());
+    let expect = "1\n\
+                  IndexError\n\
+                  No \"b\" key in Dict\n\
+                  recovered\n\
+                  True\n\
+                  Cannot lookup name: \"undefined-func\"\n"
+        .to_string();
+    assert_eq!(stdout, expect);
+
+    // `return` is control flow and passes straight through `try`
+    assert_eq!(
+        run_code("(defn 'f [] '[(try '[(return 1)] 'e '[2]) 3]) (print (f))"),
+        "1\n".to_string()
+    );
+}
+
+#[test]
+fn test_update_in() {
+    let stdout = run_file("examples/update-in.lcore".to_string());
+    let expect = "[[1 2] [4 4]]\n\
+                  2\n\
+                  2\n\
+                  Index out of bounds: got 5 but len is 2\n\
+                  ArgumentError\n\
+                  [[1 2] [4 4]]\n"
+        .to_string();
+    assert_eq!(stdout, expect);
+}
+
+#[test]
+fn test_when() {
+    let stdout = run_file("examples/when.lcore".to_string());
+    let expect = "It's True!\n\
+                  14\n\
+                  Null\n\
+                  It's False!\n\
+                  Null\n"
+        .to_string();
+    assert_eq!(stdout, expect);
+
+    assert_eq!(
+        run_code("(when 1 '[(print \"never\")])"),
+        "ArgumentError: Not a boolean (Int)\n".to_string()
+    );
+    assert_eq!(
+        run_code("(unless Null '[(print \"never\")])"),
+        "ArgumentError: Not a boolean (Null)\n".to_string()
+    );
+}
+
+#[test]
+fn test_with_output_to_string() {
+    let stdout = run_file("examples/with-output-to-string.lcore".to_string());
+    let expect = "3\n\
+                  hi\n\
+                  total: 3\n\
+                  ac\n\
+                  b\n"
+    .to_string();
+    assert_eq!(stdout, expect);
+
+    let stdout =
+        run_code("(set 's (with-output-to-string '[(print \"hi\")]))");
+    assert_eq!(stdout, "");
+}
+
+#[test]
+fn test_zip_to_dict() {
+    let stdout = run_file("examples/zip-to-dict.lcore".to_string());
+    let expect = "2\n\
+                  1\n\
+                  2\n\
+                  1\n\
+                  1\n\
+                  Array cannot be used as a Dict key\n\
+                  \"zip-to-dict\" takes two Arrays\n"
+        .to_string();
+    assert_eq!(stdout, expect);
+}